@@ -0,0 +1,20 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+
+const SCRIPTS: &[&str] = &["fib_func.rl", "counters.rl"];
+
+fn run_scripts(c: &mut Criterion) {
+  for script in SCRIPTS {
+    let path = format!("{}/test/data/{}", env!("CARGO_MANIFEST_DIR"), script);
+    let source = fs::read_to_string(&path).unwrap();
+
+    c.bench_function(script, |b| {
+      b.iter(|| rloxlib::run_with_output(&source, Rc::new(RefCell::new(std::io::sink()))))
+    });
+  }
+}
+
+criterion_group!(benches, run_scripts);
+criterion_main!(benches);
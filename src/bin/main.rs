@@ -1,24 +1,107 @@
-use clap::{App, Arg};
-use rloxlib::{run_file, run_repl};
+use clap::{App, AppSettings, Arg};
+use rloxlib::{run_file, run_repl, run_tests, FsPolicy};
+use std::path::{Path, PathBuf};
+
+// Scripts can access the working directory and their own directory,
+// tests only the working directory.
+fn fs_policy(access: &str, script: Option<&str>) -> FsPolicy {
+  let mut roots = vec![PathBuf::from(".")];
+  if let Some(dir) = script.and_then(|script| Path::new(script).parent()).filter(|dir| !dir.as_os_str().is_empty()) {
+    roots.push(dir.to_path_buf());
+  }
+
+  match access {
+    "none" => FsPolicy::disabled(),
+    "read" => FsPolicy::read_only(roots),
+    _ => FsPolicy::read_write(roots),
+  }
+}
+
+// Read-only unless write access is asked for.
+fn fs_arg() -> Arg<'static> {
+  Arg::with_name("fs")
+    .long("fs")
+    .value_name("ACCESS")
+    .possible_values(&["none", "read", "write"])
+    .default_value("read")
+    .about("File access of the scripts, none, read or write")
+}
 
 fn main() {
   let matches = App::new("rlox programming language")
+    // Without it clap rejects scripts named close to a subcommand, like te
+    // or test/x.rl, with a "did you mean 'test'" error. Only the exact
+    // word test runs tests.
+    .setting(AppSettings::AllowExternalSubcommands)
     .arg(
-      Arg::new("input")
+      Arg::with_name("input")
         .short('i')
         .long("input")
         .value_name("INPUT")
         .about("Specifiy source code input file"),
     )
+    .arg(
+      Arg::with_name("script")
+        .value_name("SCRIPT")
+        .conflicts_with("input")
+        .about("Source code file to run"),
+    )
+    .arg(
+      Arg::with_name("args")
+        .value_name("ARGS")
+        .multiple(true)
+        .last(true)
+        .about("Arguments passed to the script after --, returned by args()"),
+    )
+    .arg(fs_arg())
+    .subcommand(
+      App::new("test")
+        .about("Run test blocks from rlox source files")
+        .arg(
+          Arg::with_name("paths")
+            .value_name("PATHS")
+            .multiple(true)
+            .required(true)
+            .about("Source files or directories to search for .rl files"),
+        )
+        .arg(
+          Arg::with_name("filter")
+            .short('f')
+            .long("filter")
+            .value_name("NAME")
+            .about("Only run tests whose name contains NAME"),
+        )
+        .arg(fs_arg()),
+    )
     .get_matches();
 
-  if let Some(source_file_name) = matches.value_of("input") {
-    if let Err(err) = run_file(source_file_name) {
-      println!("Error {}", err);
+  if let ("test", Some(test_matches)) = matches.subcommand() {
+    let paths: Vec<&str> = test_matches.values_of("paths").unwrap().collect();
+    let policy = fs_policy(test_matches.value_of("fs").unwrap(), None);
+    match run_tests(&paths, test_matches.value_of("filter"), &policy) {
+      Ok(true) => (),
+      Ok(false) => std::process::exit(1),
+      Err(err) => {
+        println!("Error {}", err);
+        std::process::exit(1);
+      }
+    }
+  } else if let Some(source_file_name) = matches.value_of("input").or_else(|| matches.value_of("script")) {
+    let policy = fs_policy(matches.value_of("fs").unwrap(), Some(source_file_name));
+    let args = matches.values_of("args").map_or(vec![], |args| args.map(String::from).collect());
+    match run_file(source_file_name, args, policy) {
+      Ok(0) => (),
+      Ok(code) => std::process::exit(code),
+      Err(err) => {
+        println!("Error {}", err);
+        std::process::exit(1);
+      }
     }
   } else {
-    if let Err(err) = run_repl() {
-      println!("Error {}", err);
+    match run_repl() {
+      Ok(0) => (),
+      Ok(code) => std::process::exit(code),
+      Err(err) => println!("Error {}", err),
     }
   }
 }
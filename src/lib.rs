@@ -1,34 +1,51 @@
+mod repl;
 mod rlox;
 mod scanners;
+mod test_runner;
 
 use rlox::*;
 use scanners::Scanner;
+use std::cell::RefCell;
+use std::io::Write;
+use std::rc::Rc;
 
-use std::io::{stdin, stdout, Write};
+pub use repl::run_repl;
+pub use rlox::{collect_garbage, live_objects, FsAccess, FsPolicy};
+pub use test_runner::run_tests;
 
-pub fn run_file(filename: &str) -> Result<(), RloxError> {
+// Exit codes used by the reference lox implementations.
+pub const EXIT_COMPILE_ERROR: i32 = 65;
+pub const EXIT_RUNTIME_ERROR: i32 = 70;
+
+// Runs the script and returns the process exit code, scripts stop
+// at the first error or exit(). args are returned by args().
+pub fn run_file(filename: &str, args: Vec<String>, policy: FsPolicy) -> Result<i32, RloxError> {
     let interpreter = Interpreter::new();
+    interpreter.set_args(args);
+    interpreter.set_fs_policy(policy);
     let data = scanners::read_source_code(filename)?;
-    run(interpreter, data)
+    Ok(run_script(interpreter, data))
 }
 
-pub fn run_repl() -> Result<(), RloxError> {
-    let interpreter = Interpreter::new();
+// Runs source with everything the script prints written to output,
+// returns the process exit code. File access is disabled.
+pub fn run_with_output(source: &str, output: Rc<RefCell<dyn Write>>) -> i32 {
+    run_with_policy(source, output, FsPolicy::disabled())
+}
 
-    loop {
-        let mut data = String::new();
-        print!("> ");
-        stdout().flush()?;
-        stdin().read_line(&mut data)?;
-        run(interpreter.clone(), data.chars().collect())?;
-    }
+// Like run_with_output, with the files the script can access decided by
+// policy.
+pub fn run_with_policy(source: &str, output: Rc<RefCell<dyn Write>>, policy: FsPolicy) -> i32 {
+    let interpreter = Interpreter::new_with_output(output);
+    interpreter.set_fs_policy(policy);
+    run_script(interpreter, source.chars().collect())
 }
 
 fn repl_printer(result: Result<RloxType, RloxError>) {
     match result {
         Ok(r) => {
             if r != RloxType::NullType {
-                println!("{}", r);
+                println!("{}", r.inspect());
             }
         }
         Err(e) => {
@@ -41,16 +58,54 @@ fn print_rlox_error(e: RloxError) {
   match e {
     RloxError::ResolverError(r) => eprintln!("{}", r),
     RloxError::InterpreterError(i) => eprintln!("{}", i),
+    RloxError::ScannerError(s) => eprintln!("{}", s),
     RloxError::ParserError(p) => eprintln!("{}", p),
     _ => eprintln!("Unknown error."),
   }
 }
 
-fn run(interpreter: Interpreter, data: Vec<char>) -> Result<(), RloxError> {
+fn run_script(interpreter: Interpreter, data: Vec<char>) -> i32 {
     let mut scanner = Scanner::new(data);
-    let tokens = scanner.scan_tokens();
+    let tokens = match scanner.scan() {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            print_rlox_error(e);
+            return EXIT_COMPILE_ERROR;
+        }
+    };
     let parser = Parser::new(tokens);
-    let statements = parser.parse();
+
+    let statements = match parser.parse() {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            print_rlox_error(e);
+            return EXIT_COMPILE_ERROR;
+        }
+    };
+
+    let resolver = Resolver::new(interpreter.clone());
+    if let Err(e) = resolver.resolve_statements(statements.clone()) {
+        print_rlox_error(e);
+        return EXIT_COMPILE_ERROR;
+    }
+
+    for statement in statements {
+        match interpreter.execute(statement) {
+            Ok(_) => (),
+            Err(RloxError::Exit(code)) => return code,
+            Err(e) => {
+                print_rlox_error(e);
+                return EXIT_RUNTIME_ERROR;
+            }
+        }
+    }
+
+    0
+}
+
+fn run(interpreter: Interpreter, data: Vec<char>) -> Result<(), RloxError> {
+    let mut scanner = Scanner::new(data);
+    let statements = scanner.scan().and_then(|tokens| Parser::new(tokens).parse());
 
     match statements {
         Ok(stmt) => {
@@ -59,7 +114,12 @@ fn run(interpreter: Interpreter, data: Vec<char>) -> Result<(), RloxError> {
             print_rlox_error(e);
             return Ok(());
           }
-          interpreter.interpret(stmt, Some(repl_printer))
+          for statement in stmt {
+              match interpreter.execute(statement) {
+                  Err(RloxError::Exit(code)) => return Err(RloxError::Exit(code)),
+                  result => repl_printer(result),
+              }
+          }
         }
         Err(e) => {
             print_rlox_error(e);
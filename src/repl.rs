@@ -0,0 +1,252 @@
+use crate::rlox::*;
+use crate::scanners::{self, Scanner};
+use rustyline::{error::ReadlineError, DefaultEditor};
+use std::path::PathBuf;
+
+const PROMPT: &str = "> ";
+const CONTINUATION_PROMPT: &str = ". ";
+const HISTORY_FILE: &str = ".rlox_history";
+
+const HELP: &str = "\
+:help          Show this help
+:load <file>   Run file in the current session
+:reset         Discard all definitions and start a new session
+:env           List global variables
+:ast <expr>    Print syntax tree of an expression
+:type <expr>   Evaluate an expression in the session and print its type,
+               side effects like x++ or calls happen as usual";
+
+// Returns the exit code, exit() ends the session.
+pub fn run_repl() -> Result<i32, RloxError> {
+  let mut editor = DefaultEditor::new()?;
+  let history = history_path();
+  if let Some(path) = &history {
+    // There is no history on the first run.
+    let _ = editor.load_history(path);
+  }
+
+  let mut interpreter = Interpreter::new();
+  interpreter.set_fs_policy(FsPolicy::working_directory());
+
+  let mut code = 0;
+  while let Some(source) = read_input(&mut editor)? {
+    if source.trim().is_empty() {
+      continue;
+    }
+    editor.add_history_entry(source.trim_end())?;
+
+    match source.trim().strip_prefix(':') {
+      Some(command) => interpreter = run_command(interpreter, command),
+      None => match crate::run(interpreter.clone(), terminate_statement(&source).chars().collect()) {
+        Err(RloxError::Exit(exit_code)) => {
+          code = exit_code;
+          break;
+        }
+        result => result?,
+      },
+    }
+  }
+
+  if let Some(path) = &history {
+    editor.save_history(path)?;
+  }
+
+  Ok(code)
+}
+
+fn history_path() -> Option<PathBuf> {
+  std::env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
+
+// Reads lines until all brackets, strings and comments are closed,
+// returns None on end of input.
+fn read_input(editor: &mut DefaultEditor) -> Result<Option<String>, RloxError> {
+  let mut source = String::new();
+
+  loop {
+    let prompt = if source.is_empty() { PROMPT } else { CONTINUATION_PROMPT };
+    match editor.readline(prompt) {
+      Ok(line) => {
+        source.push_str(&line);
+        source.push('\n');
+      }
+      Err(ReadlineError::Interrupted) => {
+        // Ctrl-C discards the input typed so far.
+        source.clear();
+        continue;
+      }
+      Err(ReadlineError::Eof) => return Ok(None),
+      Err(e) => return Err(e.into()),
+    }
+
+    if source.trim_start().starts_with(':') || is_complete(&source) {
+      return Ok(Some(source));
+    }
+  }
+}
+
+pub(crate) fn is_complete(source: &str) -> bool {
+  let chars: Vec<char> = source.chars().collect();
+  let mut depth: i64 = 0;
+  let mut i = 0;
+
+  while i < chars.len() {
+    match chars[i] {
+      '(' | '{' => depth += 1,
+      ')' | '}' => depth -= 1,
+      '"' => {
+        // r"..." is raw, other strings can escape their quotes.
+        let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+        let raw = i > 0 && chars[i - 1] == 'r' && !(i > 1 && is_word(&chars[i - 2]));
+        i += 1;
+        while i < chars.len() && chars[i] != '"' {
+          i += if chars[i] == '\\' && !raw { 2 } else { 1 };
+        }
+        if i >= chars.len() {
+          return false;
+        }
+      }
+      '/' if chars.get(i + 1) == Some(&'/') => {
+        while i < chars.len() && chars[i] != '\n' {
+          i += 1;
+        }
+      }
+      '/' if chars.get(i + 1) == Some(&'*') => {
+        i += 2;
+        while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+          i += 1;
+        }
+        if i >= chars.len() {
+          return false;
+        }
+        i += 1;
+      }
+      _ => (),
+    }
+    i += 1;
+  }
+
+  depth <= 0
+}
+
+// Lets bare expressions be typed without the trailing semicolon.
+fn terminate_statement(source: &str) -> String {
+  let trimmed = source.trim_end();
+  if trimmed.ends_with(';') || trimmed.ends_with('}') {
+    source.to_string()
+  } else {
+    format!("{};", trimmed)
+  }
+}
+
+fn run_command(interpreter: Interpreter, command: &str) -> Interpreter {
+  let command = command.trim();
+  let (name, argument) = match command.find(char::is_whitespace) {
+    Some(i) => (&command[..i], command[i..].trim()),
+    None => (command, ""),
+  };
+
+  match name {
+    "help" => println!("{}", HELP),
+    "load" => match scanners::read_source_code(argument) {
+      Ok(data) => {
+        crate::run_script(interpreter.clone(), data);
+      }
+      Err(e) => eprintln!("Can't load '{}': {}", argument, e),
+    },
+    "reset" => {
+      let fresh = Interpreter::new();
+      fresh.set_fs_policy(interpreter.fs_policy());
+      return fresh;
+    }
+    "env" => {
+      for (name, value) in interpreter.globals() {
+        println!("{} = {}", name, value);
+      }
+    }
+    "ast" => match print_ast(argument) {
+      Ok(ast) => println!("{}", ast),
+      Err(e) => crate::print_rlox_error(e),
+    },
+    // Runs the expression for real, there's no way to undo its effects.
+    "type" => match evaluate(&interpreter, argument) {
+      Ok(value) => println!("{}", value.type_name()),
+      Err(e) => crate::print_rlox_error(e),
+    },
+    _ => eprintln!("Unknown command ':{}', see :help.", name),
+  }
+
+  interpreter
+}
+
+fn parse_expression<T: 'static>(source: &str) -> Result<Exp<T>, RloxError> {
+  let mut scanner = Scanner::new(format!("{};", source).chars().collect());
+  let statements = Parser::new(scanner.scan()?).parse::<T>()?;
+
+  if statements.len() == 1 {
+    if let Some(expression) = statements[0].borrow().as_any().downcast_ref::<Expression<T>>() {
+      return Ok(expression.expression.clone());
+    }
+  }
+
+  Err(RloxError::ParserError("Expect expression.".to_string()))
+}
+
+fn print_ast(source: &str) -> Result<String, RloxError> {
+  AstPrinter {}.print(parse_expression(source)?)
+}
+
+fn evaluate(interpreter: &Interpreter, source: &str) -> Result<RloxType, RloxError> {
+  let statement: Stm<RloxType> = std::rc::Rc::new(std::cell::RefCell::new(Expression::new(parse_expression(source)?)));
+  Resolver::new(interpreter.clone()).resolve_statements(vec![statement.clone()])?;
+  interpreter.execute(statement)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_input_completeness() {
+    let test_input = [
+      ("print 1;", true),
+      ("fun f() {", false),
+      ("fun f() {\n  return (1 +\n", false),
+      ("fun f() {\n  return 1;\n}", true),
+      ("print \"{\";", true),
+      ("print \"multi\nline", false),
+      ("print \"a\\\"b\";", true),
+      ("print \"a\\\\\";", true),
+      ("print r\"a\\\";", true),
+      ("print bar\"a\\\";", false),
+      ("// {", true),
+      ("/* {", false),
+      ("/* { */ print 1;", true),
+    ];
+
+    for (input, expected) in test_input.iter() {
+      assert_eq!(is_complete(input), *expected, "{}", input);
+    }
+  }
+
+  #[test]
+  fn test_ast_command() -> Result<(), RloxError> {
+    assert_eq!(print_ast("-a * (b + 2)")?, "(* (- a) (group (+ b 2)))");
+    assert!(print_ast("var a = 1").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_type_command() -> Result<(), RloxError> {
+    let interpreter = Interpreter::new();
+    crate::run_script(interpreter.clone(), "class Bagel {} var b = Bagel();".chars().collect());
+
+    assert_eq!(evaluate(&interpreter, "1 + 2")?.type_name(), "integer");
+    assert_eq!(evaluate(&interpreter, "1 + 2.5")?.type_name(), "number");
+    assert_eq!(evaluate(&interpreter, "\"a\"")?.type_name(), "string");
+    assert_eq!(evaluate(&interpreter, "clock")?.type_name(), "function");
+    assert_eq!(evaluate(&interpreter, "Bagel")?.type_name(), "class");
+    assert_eq!(evaluate(&interpreter, "b")?.type_name(), "Bagel instance");
+    Ok(())
+  }
+}
@@ -76,38 +76,69 @@ impl Visitor<String> for AstPrinter {
     self.parenthesize_expr(&expr.operator.lexeme, expr.right.clone())
   }
 
-  fn visit_variable_expr(&self, _: &Variable) -> Result<String, RloxError> {
-    // TODO: fix this
-    Err(RloxError::ParserError("Not implemented".to_string()))
+  fn visit_variable_expr(&self, expr: &Variable) -> Result<String, RloxError> {
+    Ok(expr.name.lexeme.clone())
   }
 
-  fn visit_assign_expr(&self, _: &Assign<String>) -> Result<String, RloxError> {
-    // TODO: fix this
-    Err(RloxError::ParserError("Not implemented".to_string()))
+  fn visit_assign_expr(&self, expr: &Assign<String>) -> Result<String, RloxError> {
+    self.parenthesize_expr(&format!("{} {}", expr.operator.lexeme, expr.name.lexeme), expr.value.clone())
   }
 
-  fn visit_logical_expr(&self, _: &Logical<String>) -> Result<String, RloxError> {
-    Err(RloxError::ParserError("Not implemented".to_string()))
+  fn visit_logical_expr(&self, expr: &Logical<String>) -> Result<String, RloxError> {
+    self.parenthesize_expr_pair(&expr.operator.lexeme, expr.left.clone(), expr.right.clone())
+  }
+
+  fn visit_interpolation_expr(&self, expr: &Interpolation<String>) -> Result<String, RloxError> {
+    let mut text = String::from("(interpolate");
+
+    for part in &expr.parts {
+      text.push(' ');
+      text.push_str(&part.borrow().accept(Rc::new(RefCell::new(self.clone())))?);
+    }
+
+    text.push(')');
+
+    Ok(text)
   }
 
-  fn visit_call_expr(&self, _: &Call<String>) -> Result<String, RloxError> {
-    Err(RloxError::ParserError("Not implemented".to_string()))
+  fn visit_conditional_expr(&self, expr: &Conditional<String>) -> Result<String, RloxError> {
+    let condition = expr.condition.borrow().accept(Rc::new(RefCell::new(self.clone())))?;
+    self.parenthesize_expr_pair(&format!("? {}", condition), expr.then_branch.clone(), expr.else_branch.clone())
   }
 
-  fn visit_get_expr(&self, _: &Get<String>) -> Result<String, RloxError> {
-    Err(RloxError::ParserError("Not implemented".to_string()))
+  fn visit_optional_chain_expr(&self, expr: &OptionalChain<String>) -> Result<String, RloxError> {
+    self.parenthesize_expr("?.", expr.expression.clone())
+  }
+
+  fn visit_call_expr(&self, expr: &Call<String>) -> Result<String, RloxError> {
+    let mut text = String::from("(call ");
+    text.push_str(&expr.callee.borrow().accept(Rc::new(RefCell::new(self.clone())))?);
+
+    for argument in &expr.arguments {
+      text.push(' ');
+      text.push_str(&argument.borrow().accept(Rc::new(RefCell::new(self.clone())))?);
+    }
+
+    text.push(')');
+
+    Ok(text)
   }
 
-  fn visit_set_expr(&self, _: &Set<String>) -> Result<String, RloxError> {
-    Err(RloxError::ParserError("Not implemented".to_string()))
+  fn visit_get_expr(&self, expr: &Get<String>) -> Result<String, RloxError> {
+    let operator = if expr.optional { "?." } else { "." };
+    self.parenthesize_expr(&format!("{} {}", operator, expr.name.lexeme), expr.object.clone())
+  }
+
+  fn visit_set_expr(&self, expr: &Set<String>) -> Result<String, RloxError> {
+    self.parenthesize_expr_pair(&format!("{} {}", expr.operator.lexeme, expr.name.lexeme), expr.object.clone(), expr.value.clone())
   }
 
   fn visit_this_expr(&self, _: &This) -> Result<String, RloxError> {
-    Err(RloxError::ParserError("Not implemented".to_string()))
+    Ok("this".to_string())
   }
 
-  fn visit_super_expr(&self, _: &Super) -> Result<String, RloxError> {
-    Err(RloxError::ParserError("Not implemented".to_string()))
+  fn visit_super_expr(&self, expr: &Super) -> Result<String, RloxError> {
+    Ok(format!("(super {})", expr.method.lexeme))
   }
 }
 
@@ -115,6 +146,7 @@ impl Visitor<String> for AstPrinter {
 mod tests {
   use super::*;
   use crate::rlox::*;
+  use crate::scanners::Scanner;
 
   #[test]
   fn print_simple_ast() -> Result<(), RloxError> {
@@ -137,4 +169,28 @@ mod tests {
     assert_eq!(scanned_expression, "(* (- 123) (group 45.67))");
     Ok(())
   }
+
+  #[test]
+  fn print_parsed_ast() -> Result<(), RloxError> {
+    let mut scanner = Scanner::new("a.b = f(1, x or y);".chars().collect());
+    let statements = Parser::new(scanner.scan()?).parse::<String>()?;
+    let statement = statements[0].borrow();
+    let expression = statement.as_any().downcast_ref::<Expression<String>>().unwrap();
+
+    let ast_printer = AstPrinter {};
+    assert_eq!(ast_printer.print(expression.expression.clone())?, "(= b a (call f 1 (or x y)))");
+    Ok(())
+  }
+
+  #[test]
+  fn print_conditional_operators() -> Result<(), RloxError> {
+    let mut scanner = Scanner::new("a ? b ?? c : d?.e.f();".chars().collect());
+    let statements = Parser::new(scanner.scan()?).parse::<String>()?;
+    let statement = statements[0].borrow();
+    let expression = statement.as_any().downcast_ref::<Expression<String>>().unwrap();
+
+    let ast_printer = AstPrinter {};
+    assert_eq!(ast_printer.print(expression.expression.clone())?, "(? a (?? b c) (?. (call (. f (?. e d)))))");
+    Ok(())
+  }
 }
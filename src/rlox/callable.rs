@@ -1,7 +1,10 @@
 use super::{
+  gc::GcRef,
   interpreter::{ Interpreter },
   rlox_type::RloxType,
   rlox_errors::RloxError,
+  rlox_function::RloxFunction,
+  rlox_class::RloxClass,
 };
 
 pub trait Callable: CallableClone {
@@ -9,6 +12,19 @@ pub trait Callable: CallableClone {
   fn call(&self, interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError>;
   fn name(&self) -> String;
   fn as_any(&self) -> &dyn std::any::Any;
+
+  fn inspect(&self) -> String {
+    self.name()
+  }
+
+  // Variadic callables take any number of arguments after the first
+  // arity() ones.
+  fn variadic(&self) -> bool {
+    false
+  }
+
+  // Callables holding on to environments or instances report them here.
+  fn trace(&self, _refs: &mut Vec<GcRef>) {}
 }
 
 pub trait CallableClone {
@@ -37,8 +53,17 @@ impl std::fmt::Debug for Box<dyn Callable> {
   }
 }
 
+// Functions and classes are only equal to themselves, natives to natives
+// of the same type and name.
 impl PartialEq for Box<dyn Callable> {
   fn eq(&self, other: &Self) -> bool {
-      self == other
+    let (left, right) = (self.as_any(), other.as_any());
+    if let (Some(l), Some(r)) = (left.downcast_ref::<RloxFunction>(), right.downcast_ref::<RloxFunction>()) {
+      return l == r;
+    }
+    if let (Some(l), Some(r)) = (left.downcast_ref::<RloxClass>(), right.downcast_ref::<RloxClass>()) {
+      return l == r;
+    }
+    left.type_id() == right.type_id() && self.name() == other.name()
   }
 }
@@ -4,12 +4,27 @@ use std::cell::RefCell;
 use super::{
   rlox_type::*,
   native_functions::*,
+  math,
+  time,
+  fs,
+  json,
+  regex,
   rlox_errors::RloxError,
+  gc::{self, GcRef, Trace, Slots, Values},
 };
 
+#[derive(Debug, Clone)]
+enum Scope {
+  // Globals are late bound, they are looked up by name.
+  Globals(Values),
+  // Locals are kept in the order they are declared in, which is the
+  // slot index the resolver assigns to them.
+  Locals(Slots),
+}
+
 #[derive(Debug, Clone)]
 pub struct Environment {
-  values: Rc<RefCell<HashMap<String, RloxType>>>,
+  scope: Scope,
   pub enclosing: Option<Rc<RefCell<Environment>>>,
 }
 
@@ -18,16 +33,39 @@ impl Environment {
     let mut env_map = HashMap::new();
 
     env_map.insert("clock".to_string(), RloxType::CallableType(Box::new(Clock::new())));
-
+    env_map.insert("assert".to_string(), RloxType::CallableType(Box::new(Assert::new())));
+    env_map.insert("assert_eq".to_string(), RloxType::CallableType(Box::new(AssertEq::new())));
+    env_map.insert("assert_throws".to_string(), RloxType::CallableType(Box::new(AssertThrows::new())));
+    env_map.insert("inspect".to_string(), RloxType::CallableType(Box::new(Inspect::new())));
+    env_map.insert("gc".to_string(), RloxType::CallableType(Box::new(Gc::new())));
+    env_map.insert("number".to_string(), RloxType::CallableType(Box::new(ToNumber::new())));
+    env_map.insert("bigint".to_string(), RloxType::CallableType(Box::new(ToBigInt::new())));
+    env_map.insert("decimal".to_string(), RloxType::CallableType(Box::new(ToDecimal::new())));
+    env_map.insert("args".to_string(), RloxType::CallableType(Box::new(Args::new())));
+    env_map.insert("readLine".to_string(), RloxType::CallableType(Box::new(ReadLine::new())));
+    env_map.insert("readAll".to_string(), RloxType::CallableType(Box::new(ReadAll::new())));
+    env_map.insert("env".to_string(), RloxType::CallableType(Box::new(Env::new())));
+    env_map.insert("exit".to_string(), RloxType::CallableType(Box::new(Exit::new())));
+    env_map.insert("format".to_string(), RloxType::CallableType(Box::new(Format::new())));
+    env_map.insert("math".to_string(), math::namespace());
+    env_map.insert("time".to_string(), time::namespace());
+    env_map.insert("fs".to_string(), fs::namespace());
+    env_map.insert("json".to_string(), json::namespace());
+    env_map.insert("Regex".to_string(), regex::namespace());
+
+    let values = Rc::new(RefCell::new(env_map));
+    gc::track_values(&values);
     Environment {
-      values: Rc::new(RefCell::new(env_map)),
+      scope: Scope::Globals(values),
       enclosing: None,
     }
   }
 
   pub fn new_with_parent(enclosing: Environment)-> Environment {
+    let slots = Rc::new(RefCell::new(Vec::new()));
+    gc::track_slots(&slots);
     Environment {
-      values: Rc::new(RefCell::new(HashMap::new())),
+      scope: Scope::Locals(slots),
       enclosing: Some(Rc::new(RefCell::new(enclosing))),
     }
   }
@@ -39,70 +77,103 @@ impl Environment {
     }
   }
 
+  pub fn values(&self) -> Vec<(String, RloxType)> {
+    match &self.scope {
+      Scope::Globals(values) => {
+        let mut values: Vec<(String, RloxType)> = values.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        values.sort_by(|a, b| a.0.cmp(&b.0));
+        values
+      }
+      Scope::Locals(_) => Vec::new(),
+    }
+  }
+
+  // Locals are appended, in the same order the resolver declared them.
   pub fn define(&self, name: String, expr: RloxType) {
-    self.values.borrow_mut().insert(name, expr);
+    match &self.scope {
+      Scope::Globals(values) => {
+        values.borrow_mut().insert(name, expr);
+      }
+      Scope::Locals(slots) => slots.borrow_mut().push(expr),
+    }
   }
 
   pub fn get(&self, name: &str) -> Result<RloxType, RloxError> {
-    match self.values.borrow().get(name) {
-      Some(v) => Ok(v.clone()),
-      None => {
-        if let Some(encl) = &self.enclosing {
-          return encl.borrow().get(name);
-        }
-
-        Err(RloxError::InterpreterError(format!("Undefined variable '{}'.", name)))
+    if let Scope::Globals(values) = &self.scope {
+      if let Some(v) = values.borrow().get(name) {
+        return Ok(v.clone());
       }
     }
+
+    if let Some(encl) = &self.enclosing {
+      return encl.borrow().get(name);
+    }
+
+    Err(RloxError::InterpreterError(format!("Undefined variable '{}'.", name)))
   }
 
   pub fn assign(&self, name: &str, value: RloxType) -> Result<(), RloxError> {
-    let mut values = self.values.borrow_mut();
-    match values.get(name) {
-      Some(_) => {
-        values.insert(name.to_string(), value);
-        Ok(())
+    if let Scope::Globals(values) = &self.scope {
+      let mut values = values.borrow_mut();
+      if let Some(v) = values.get_mut(name) {
+        *v = value;
+        return Ok(());
       }
-      None => {
-        if let Some(encl) = &self.enclosing {
-          return encl.borrow().assign(name, value);
-        }
+    }
 
-        Err(RloxError::InterpreterError(format!("Undefined variable '{}'.", name)))
-      }
+    if let Some(encl) = &self.enclosing {
+      return encl.borrow().assign(name, value);
     }
+
+    Err(RloxError::InterpreterError(format!("Undefined variable '{}'.", name)))
   }
 
-  pub fn assign_at(&self, distance: usize, name: &str, value: RloxType) -> Result<(), RloxError> {
-    match self.ancestor(distance) {
-      Some(env) => {
-        env.values.borrow_mut().insert(name.to_string(), value);
-        Ok(())
+  pub fn assign_at(&self, distance: usize, slot: usize, value: RloxType) -> Result<(), RloxError> {
+    if distance > 0 {
+      return match &self.enclosing {
+        Some(encl) => encl.borrow().assign_at(distance - 1, slot, value),
+        None => Err(RloxError::InterpreterError("Internal interpreter error, invalid environment distance.".to_string())),
       }
-      None => Err(RloxError::InterpreterError("Internal interpreter error, invalid environment distance.".to_string())),
     }
-  }
 
-  pub fn get_at(&self, distance: usize, name: &str) -> Result<RloxType, RloxError> {
-    match self.ancestor(distance) {
-      Some(env) => match env.values.borrow().get(name) {
-        Some(v) => Ok(v.clone()),
-        None => Err(RloxError::InterpreterError(format!("Variable {} not found in environment.", name)))
+    match &self.scope {
+      Scope::Locals(slots) => match slots.borrow_mut().get_mut(slot) {
+        Some(v) => {
+          *v = value;
+          Ok(())
+        }
+        None => Err(RloxError::InterpreterError(format!("Internal interpreter error, invalid slot {}.", slot))),
       }
-      None => Err(RloxError::InterpreterError("Internal interpreter error, invalid environment distance.".to_string())),
+      Scope::Globals(_) => Err(RloxError::InterpreterError("Internal interpreter error, globals have no slots.".to_string())),
     }
   }
 
-  fn ancestor(&self, distance: usize) -> Option<Environment> {
-    let mut current_env = self.clone();
+  pub fn get_at(&self, distance: usize, slot: usize) -> Result<RloxType, RloxError> {
+    if distance > 0 {
+      return match &self.enclosing {
+        Some(encl) => encl.borrow().get_at(distance - 1, slot),
+        None => Err(RloxError::InterpreterError("Internal interpreter error, invalid environment distance.".to_string())),
+      }
+    }
 
-    for _ in 0..distance {
-      current_env = match current_env.enclosing {
-        Some(e) => e.borrow().clone(),
-        None => return None,
+    match &self.scope {
+      Scope::Locals(slots) => match slots.borrow().get(slot) {
+        Some(v) => Ok(v.clone()),
+        None => Err(RloxError::InterpreterError(format!("Internal interpreter error, invalid slot {}.", slot))),
       }
+      Scope::Globals(_) => Err(RloxError::InterpreterError("Internal interpreter error, globals have no slots.".to_string())),
     }
+  }
+}
 
-    Some(current_env)
+impl Trace for Environment {
+  fn trace(&self, refs: &mut Vec<GcRef>) {
+    match &self.scope {
+      Scope::Globals(values) => refs.push(GcRef::Values(values.clone())),
+      Scope::Locals(slots) => refs.push(GcRef::Slots(slots.clone())),
+    }
+    if let Some(enclosing) = &self.enclosing {
+      refs.push(GcRef::Environment(enclosing.clone()));
+    }
   }
 }
@@ -1,3 +1,3 @@
-pub fn report(line: usize, message: &str) {
-  println!("Line[{}] Error: {}", line, message);
+pub fn error_message(line: usize, message: &str) -> String {
+  format!("[line {}] Error: {}", line, message)
 }
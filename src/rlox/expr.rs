@@ -7,34 +7,45 @@ use std::rc::Rc;
 use crate::generate_ast;
 
 // expression     → assignment ;
-// assignment     → IDENTIFIER "=" assignment
-//                | logic_or ;
+// assignment     → ( call "." )? IDENTIFIER ( "=" | "+=" | "-=" | "*=" | "/=" | "%=" ) assignment
+//                | conditional ;
+// conditional    → coalesce ( "?" expression ":" conditional )? ;
+// coalesce       → logic_or ( "??" logic_or )* ;
 // logic_or       → logic_and ( "or" logic_and )* ;
 // logic_and      → equality ( "and" equality )* ;
-// expression     → equality ;
 // equality       → comparison ( ( "!=" | "==" ) comparison )* ;
 // comparison     → term ( ( ">" | ">=" | "<" | "<=" ) term )* ;
 // term           → factor ( ( "-" | "+" ) factor )* ;
-// factor         → unary ( ( "/" | "*" ) unary )* ;
-// unary          → ( "!" | "-" ) unary | call ;
-// call           → primary ( "(" arguments? ")" )* ;
+// factor         → bit_or ( ( "/" | "*" | "%" | "div" ) bit_or )* ;
+// bit_or         → bit_xor ( "|" bit_xor )* ;
+// bit_xor        → bit_and ( "^" bit_and )* ;
+// bit_and        → shift ( "&" shift )* ;
+// shift          → unary ( ( "<<" | ">>" ) unary )* ;
+// unary          → ( "!" | "-" | "~" | "++" | "--" ) unary | exponent ;
+// exponent       → postfix ( "**" unary )? ;
+// postfix        → call ( "++" | "--" )? ;
+// call           → primary ( "(" arguments? ")" | ( "." | "?." ) IDENTIFIER )* ;
 // arguments      → expression ( "," expression )* ;
 // primary        → "true" | "false" | "nil" | "this"
 //                | NUMBER | STRING | IDENTIFIER | "(" expression ")"
+//                | ( INTERPOLATION expression )+ STRING
 //                | "super" "." IDENTIFIER ;
 
 pub type Exp<T> = Rc<RefCell<dyn Expr<T>>>;
 
 generate_ast! {
   Expr {
-    visit_assign_expr Assign T => name: Token, value: Exp<T>;
+    visit_assign_expr Assign T => name: Token, operator: Token, value: Exp<T>, postfix: bool;
     visit_binary_expr Binary T => left: Exp<T>, operator: Token, right: Exp<T>;
     visit_call_expr Call T => callee: Exp<T>, parent: Token, arguments: Vec<Exp<T>>;
-    visit_get_expr Get T => object: Exp<T>, name: Token;
+    visit_conditional_expr Conditional T => condition: Exp<T>, then_branch: Exp<T>, else_branch: Exp<T>;
+    visit_get_expr Get T => object: Exp<T>, name: Token, optional: bool;
     visit_grouping_expr Grouping T => expression: Exp<T>;
+    visit_interpolation_expr Interpolation T => parts: Vec<Exp<T>>;
     visit_literal_expr LiteralObj => value: Option<Literal>;
     visit_logical_expr Logical T => left: Exp<T>, operator: Token, right: Exp<T>;
-    visit_set_expr Set T => object: Exp<T>, name: Token, value: Exp<T>;
+    visit_optional_chain_expr OptionalChain T => expression: Exp<T>;
+    visit_set_expr Set T => object: Exp<T>, name: Token, operator: Token, value: Exp<T>, postfix: bool;
     visit_super_expr Super => keyword: Token, method: Token;
     visit_this_expr This => keyword: Token;
     visit_unary_expr Unary T => operator: Token, right: Exp<T>;
@@ -95,7 +106,9 @@ impl<T> Clone for Assign<T> {
   fn clone(&self) -> Self {
     Assign {
       name: self.name.clone(),
+      operator: self.operator.clone(),
       value: self.value.clone(),
+      postfix: self.postfix,
       id: self.id,
     }
   }
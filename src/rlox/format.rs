@@ -0,0 +1,283 @@
+// Placeholders of format(), modelled on Rust's format strings.
+//
+//   {}  {0}  {name}  {:spec}  {0:spec}  {{  }}
+//   spec = [[fill]align][+][#][0][width][.precision][type]
+//
+// align is <, ^ or >, type is x, X, o, b, e or E. Named placeholders read
+// the fields of the last argument, which has to be an instance. Values
+// without a type are written like print writes them.
+use num_bigint::BigInt;
+use num_traits::Signed;
+use super::{
+  rlox_type::RloxType,
+  rlox_errors::RloxError,
+  numeric,
+};
+
+// Largest width or precision, larger ones can't be formatted or allocated.
+const MAX_WIDTH: usize = u16::MAX as usize;
+
+#[derive(Default)]
+struct Spec {
+  fill: Option<char>,
+  align: Option<char>,
+  plus: bool,
+  alternate: bool,
+  zero: bool,
+  width: usize,
+  precision: Option<usize>,
+  kind: Option<char>,
+}
+
+fn error(message: String) -> RloxError {
+  RloxError::InterpreterError(format!("format() {}", message))
+}
+
+pub fn format(template: &str, arguments: &[RloxType]) -> Result<String, RloxError> {
+  let chars: Vec<char> = template.chars().collect();
+  let mut text = String::new();
+  let mut next = 0;
+  let mut i = 0;
+
+  while i < chars.len() {
+    match chars[i] {
+      '{' if chars.get(i + 1) == Some(&'{') => {
+        text.push('{');
+        i += 2;
+      }
+      '}' if chars.get(i + 1) == Some(&'}') => {
+        text.push('}');
+        i += 2;
+      }
+      '}' => return Err(error(format!("found an unmatched '}}' at {}.", i))),
+      '{' => {
+        let end = match chars[i..].iter().position(|&c| c == '}') {
+          Some(offset) => i + offset,
+          None => return Err(error(format!("found an unclosed '{{' at {}.", i))),
+        };
+        let placeholder: String = chars[i + 1..end].iter().collect();
+        let (key, spec) = match placeholder.split_once(':') {
+          Some((key, spec)) => (key, parse_spec(spec)?),
+          None => (placeholder.as_str(), Spec::default()),
+        };
+
+        let value = argument(key, arguments, &mut next)?;
+        text.push_str(&apply(&spec, &value)?);
+        i = end + 1;
+      }
+      c => {
+        text.push(c);
+        i += 1;
+      }
+    }
+  }
+
+  Ok(text)
+}
+
+fn argument(key: &str, arguments: &[RloxType], next: &mut usize) -> Result<RloxType, RloxError> {
+  let key = key.trim();
+
+  if key.is_empty() {
+    *next += 1;
+    return arguments.get(*next - 1).cloned().ok_or_else(|| error(format!("needs more than {} arguments.", arguments.len())));
+  }
+
+  if let Ok(index) = key.parse::<usize>() {
+    return arguments.get(index).cloned().ok_or_else(|| error(format!("has no argument {{{}}}.", index)));
+  }
+
+  match arguments.last() {
+    Some(RloxType::ClassType(instance)) => instance.fields().into_iter()
+      .find(|(name, _)| name == key)
+      .map(|(_, value)| value)
+      .ok_or_else(|| error(format!("has no field {{{}}} in its last argument.", key))),
+    _ => Err(error(format!("needs an instance as last argument for {{{}}}.", key))),
+  }
+}
+
+fn parse_spec(spec: &str) -> Result<Spec, RloxError> {
+  let chars: Vec<char> = spec.chars().collect();
+  let mut result = Spec::default();
+  let mut i = 0;
+  let is_align = |c: Option<&char>| matches!(c, Some('<' | '^' | '>'));
+
+  if is_align(chars.get(1)) {
+    result.fill = Some(chars[0]);
+    result.align = Some(chars[1]);
+    i = 2;
+  } else if is_align(chars.first()) {
+    result.align = Some(chars[0]);
+    i = 1;
+  }
+
+  if chars.get(i) == Some(&'+') {
+    result.plus = true;
+    i += 1;
+  }
+  if chars.get(i) == Some(&'#') {
+    result.alternate = true;
+    i += 1;
+  }
+  if chars.get(i) == Some(&'0') {
+    result.zero = true;
+    i += 1;
+  }
+
+  // Digits at i, None when there are none and an error above MAX_WIDTH.
+  let number = |i: &mut usize, what: &str| -> Result<Option<usize>, RloxError> {
+    let start = *i;
+    while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+      *i += 1;
+    }
+    if start == *i {
+      return Ok(None);
+    }
+    match chars[start..*i].iter().collect::<String>().parse::<usize>() {
+      Ok(n) if n <= MAX_WIDTH => Ok(Some(n)),
+      _ => Err(error(format!("has a {} larger than {} in '{{:{}}}'.", what, MAX_WIDTH, spec))),
+    }
+  };
+
+  result.width = number(&mut i, "width")?.unwrap_or(0);
+  if chars.get(i) == Some(&'.') {
+    i += 1;
+    result.precision = Some(number(&mut i, "precision")?.ok_or_else(|| error(format!("expects a precision after '.' in '{{:{}}}'.", spec)))?);
+  }
+
+  match &chars[i..] {
+    [] => (),
+    [c @ ('x' | 'X' | 'o' | 'b' | 'e' | 'E')] => result.kind = Some(*c),
+    _ => return Err(error(format!("can't understand '{{:{}}}'.", spec))),
+  }
+
+  Ok(result)
+}
+
+fn integer(value: &RloxType) -> Option<BigInt> {
+  match value {
+    RloxType::IntegerType(i) => Some(BigInt::from(*i)),
+    RloxType::BigIntType(b) => Some(b.clone()),
+    _ => None,
+  }
+}
+
+// Text of the value and whether it's a number, numbers are aligned right
+// and zero padded after their sign.
+fn apply(spec: &Spec, value: &RloxType) -> Result<String, RloxError> {
+  let is_number = numeric::is_number(value);
+
+  let text = match spec.kind {
+    Some(kind @ ('x' | 'X' | 'o' | 'b')) => {
+      let n = integer(value).ok_or_else(|| error(format!("can only write integers with '{}', not {}.", kind, value.type_name())))?;
+      let (radix, prefix) = match kind {
+        'o' => (8, "0o"),
+        'b' => (2, "0b"),
+        _ => (16, "0x"),
+      };
+      let digits = n.abs().to_str_radix(radix);
+      let digits = if kind == 'X' { digits.to_uppercase() } else { digits };
+      let sign = if n.is_negative() { "-" } else { "" };
+      format!("{}{}{}", sign, if spec.alternate { prefix } else { "" }, digits)
+    }
+    Some(kind) => {
+      let n = numeric::to_float(value).ok_or_else(|| error(format!("can only write numbers with '{}', not {}.", kind, value.type_name())))?;
+      let text = match spec.precision {
+        Some(precision) => format!("{:.*e}", precision, n),
+        None => format!("{:e}", n),
+      };
+      if kind == 'E' { text.to_uppercase() } else { text }
+    }
+    None => match (spec.precision, value) {
+      (Some(precision), RloxType::NumberType(n)) => format!("{:.*}", precision, n),
+      (Some(precision), RloxType::IntegerType(i)) => format!("{:.*}", precision, *i as f64),
+      (Some(precision), RloxType::DecimalType(d)) => format!("{:.*}", precision, d),
+      (Some(precision), RloxType::StringType(s)) => s.chars().take(precision).collect(),
+      _ => value.to_string(),
+    },
+  };
+
+  let text = if spec.plus && is_number && !text.starts_with('-') { format!("+{}", text) } else { text };
+  Ok(pad(spec, text, is_number))
+}
+
+fn pad(spec: &Spec, text: String, is_number: bool) -> String {
+  let length = text.chars().count();
+  if length >= spec.width {
+    return text;
+  }
+  let missing = spec.width - length;
+
+  // Zeros go between the sign or prefix and the digits.
+  if spec.zero && is_number && spec.align.is_none() {
+    let sign = text.chars().take_while(|c| matches!(c, '+' | '-')).count();
+    let prefix = if text[sign..].starts_with("0x") || text[sign..].starts_with("0o") || text[sign..].starts_with("0b") { 2 } else { 0 };
+    let (head, digits) = text.split_at(sign + prefix);
+    return format!("{}{}{}", head, "0".repeat(missing), digits);
+  }
+
+  let fill = spec.fill.unwrap_or(' ').to_string();
+  let align = spec.align.unwrap_or(if is_number { '>' } else { '<' });
+  match align {
+    '>' => format!("{}{}", fill.repeat(missing), text),
+    '^' => format!("{}{}{}", fill.repeat(missing / 2), text, fill.repeat(missing - missing / 2)),
+    _ => format!("{}{}", text, fill.repeat(missing)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_format() -> Result<(), RloxError> {
+    let args = [
+      RloxType::NumberType(1.23456),
+      RloxType::IntegerType(255),
+      RloxType::StringType("rlox".to_string()),
+      RloxType::IntegerType(-42),
+    ];
+    let cases = [
+      ("{} {} {}", "1.23456 255 rlox"),
+      ("{2} {0:.2} {1}", "rlox 1.23 255"),
+      ("[{:>8.2}]", "[    1.23]"),
+      ("[{:<8.2}]", "[1.23    ]"),
+      ("[{:^9.1}]", "[   1.2   ]"),
+      ("[{2:*^8}]", "[**rlox**]"),
+      ("[{2:8}] [{1:6}]", "[rlox    ] [   255]"),
+      ("{1:x} {1:X} {1:#x} {1:b} {1:#o}", "ff FF 0xff 11111111 0o377"),
+      ("{3:x} {3:#06x} {3:+} {1:+} {1:05}", "-2a -0x02a -42 +255 00255"),
+      ("{0:e} {1:.2E} {0:.3e}", "1.23456e0 2.55E2 1.235e0"),
+      ("{1:.2} {2:.2}", "255.00 rl"),
+      ("{{}} {{{}}}", "{} {1.23456}"),
+    ];
+
+    for (template, expected) in cases.iter() {
+      assert_eq!(format(template, &args)?, *expected, "{}", template);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_format_errors() {
+    let args = [RloxType::StringType("a".to_string())];
+    let cases = [
+      ("{} {}", "format() needs more than 1 arguments."),
+      ("{3}", "format() has no argument {3}."),
+      ("{name}", "format() needs an instance as last argument for {name}."),
+      ("{:x}", "format() can only write integers with 'x', not string."),
+      ("{:q}", "format() can't understand '{:q}'."),
+      ("{:.}", "format() expects a precision after '.' in '{:.}'."),
+      ("{:.1000000000000}", "format() has a precision larger than 65535 in '{:.1000000000000}'."),
+      ("{:1000000000000}", "format() has a width larger than 65535 in '{:1000000000000}'."),
+      ("{:99999999999999999999999}", "format() has a width larger than 65535 in '{:99999999999999999999999}'."),
+      ("{:65536}", "format() has a width larger than 65535 in '{:65536}'."),
+      ("open {", "format() found an unclosed '{' at 5."),
+      ("close }", "format() found an unmatched '}' at 6."),
+    ];
+
+    for (template, expected) in cases.iter() {
+      assert_eq!(format(template, &args).unwrap_err().to_string(), *expected, "{}", template);
+    }
+  }
+}
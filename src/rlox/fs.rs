@@ -0,0 +1,292 @@
+// The fs namespace.
+//
+// What scripts may touch is decided by the embedder through an FsPolicy,
+// paths are resolved, symlinks included, and have to stay inside one of
+// the allowed roots. Embedded interpreters start with file access
+// disabled, the rlox binary allows the working directory.
+use std::{
+  fs,
+  io::{self, Write},
+  path::{Component, Path, PathBuf},
+};
+use super::{
+  callable::Callable,
+  interpreter::Interpreter,
+  native_object::NativeObject,
+  rlox_type::RloxType,
+  rlox_errors::RloxError,
+  rlox_list::RloxList,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FsAccess {
+  Disabled,
+  ReadOnly,
+  ReadWrite,
+}
+
+#[derive(Clone, Debug)]
+pub struct FsPolicy {
+  access: FsAccess,
+  roots: Vec<PathBuf>,
+}
+
+impl FsPolicy {
+  pub fn disabled() -> FsPolicy {
+    FsPolicy { access: FsAccess::Disabled, roots: vec![] }
+  }
+
+  pub fn read_only(roots: Vec<PathBuf>) -> FsPolicy {
+    FsPolicy { access: FsAccess::ReadOnly, roots }
+  }
+
+  pub fn read_write(roots: Vec<PathBuf>) -> FsPolicy {
+    FsPolicy { access: FsAccess::ReadWrite, roots }
+  }
+
+  // Read and write access to the current directory.
+  pub fn working_directory() -> FsPolicy {
+    FsPolicy::read_write(vec![PathBuf::from(".")])
+  }
+
+  pub fn access(&self) -> FsAccess {
+    self.access
+  }
+
+  // The resolved path when the policy allows the access.
+  fn check(&self, path: &str, write: bool) -> Result<PathBuf, String> {
+    match self.access {
+      FsAccess::Disabled => return Err("file system access is disabled".to_string()),
+      FsAccess::ReadOnly if write => return Err("file system is read-only".to_string()),
+      _ => (),
+    }
+
+    let denied = || "access denied".to_string();
+    let resolved = resolve(Path::new(path)).ok_or_else(denied)?;
+    let allowed = self.roots.iter()
+      .filter_map(|root| fs::canonicalize(root).ok())
+      .any(|root| resolved.starts_with(root));
+
+    if allowed { Ok(resolved) } else { Err(denied()) }
+  }
+}
+
+impl Default for FsPolicy {
+  fn default() -> FsPolicy {
+    FsPolicy::disabled()
+  }
+}
+
+// Absolute path with symlinks of the existing part resolved, the part that
+// doesn't exist yet can't step out with ".." or through a dangling symlink.
+fn resolve(path: &Path) -> Option<PathBuf> {
+  let absolute = std::env::current_dir().ok()?.join(path);
+  let mut existing = absolute.as_path();
+  let mut missing = vec![];
+
+  let base = loop {
+    match fs::canonicalize(existing) {
+      Ok(base) => break base,
+      Err(_) => {
+        if fs::symlink_metadata(existing).is_ok() {
+          return None;
+        }
+        missing.push(existing.file_name()?);
+        existing = existing.parent()?;
+      }
+    }
+  };
+
+  let mut resolved = base;
+  for part in missing.into_iter().rev() {
+    if Path::new(part).components().any(|c| !matches!(c, Component::Normal(_))) {
+      return None;
+    }
+    resolved.push(part);
+  }
+  Some(resolved)
+}
+
+// The checked path may have been replaced with a symlink since, so the
+// file itself isn't followed if it is one.
+fn open_for_write(path: &Path, append: bool) -> io::Result<fs::File> {
+  let mut options = fs::OpenOptions::new();
+  options.create(true);
+  if append {
+    options.append(true);
+  } else {
+    options.write(true).truncate(true);
+  }
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.custom_flags(libc::O_NOFOLLOW);
+  }
+
+  let file = options.open(path)?;
+  if !file.metadata()?.is_file() {
+    return Err(io::Error::new(io::ErrorKind::PermissionDenied, "not a regular file"));
+  }
+  Ok(file)
+}
+
+fn describe(e: io::Error) -> String {
+  match e.kind() {
+    io::ErrorKind::NotFound => "not found".to_string(),
+    io::ErrorKind::PermissionDenied => "permission denied".to_string(),
+    io::ErrorKind::AlreadyExists => "already exists".to_string(),
+    io::ErrorKind::InvalidData => "not valid UTF-8".to_string(),
+    _ => e.to_string(),
+  }
+}
+
+const FUNCTIONS: &[(&str, usize, bool)] = &[
+  ("read", 1, false),
+  ("lines", 1, false),
+  ("exists", 1, false),
+  ("list", 1, false),
+  ("write", 2, true),
+  ("append", 2, true),
+  ("mkdir", 1, true),
+  ("remove", 1, true),
+];
+
+pub fn namespace() -> RloxType {
+  let members = FUNCTIONS.iter()
+    .map(|(name, arity, write)| (*name, RloxType::CallableType(Box::new(FsFunction { name, arity: *arity, write: *write }))))
+    .collect();
+
+  RloxType::NativeType(NativeObject::new("fs", members))
+}
+
+#[derive(Clone)]
+struct FsFunction {
+  name: &'static str,
+  arity: usize,
+  write: bool,
+}
+
+impl FsFunction {
+  fn run(&self, path: &Path, arguments: &[RloxType]) -> io::Result<RloxType> {
+    let text = || match arguments.get(1) {
+      Some(RloxType::StringType(s)) => s.clone(),
+      Some(value) => value.to_string(),
+      None => String::new(),
+    };
+
+    match self.name {
+      "read" => fs::read_to_string(path).map(RloxType::StringType),
+      "lines" => {
+        let content = fs::read_to_string(path)?;
+        let lines = content.lines().map(|line| RloxType::StringType(line.to_string())).collect();
+        Ok(RloxType::ListType(RloxList::new(lines)))
+      }
+      "exists" => Ok(RloxType::BooleanType(path.exists())),
+      "list" => {
+        let mut names = fs::read_dir(path)?
+          .map(|entry| entry.map(|e| e.file_name().to_string_lossy().into_owned()))
+          .collect::<io::Result<Vec<String>>>()?;
+        names.sort();
+        Ok(RloxType::ListType(RloxList::new(names.into_iter().map(RloxType::StringType).collect())))
+      }
+      "write" | "append" => {
+        let mut file = open_for_write(path, self.name == "append")?;
+        file.write_all(text().as_bytes()).map(|_| RloxType::NullType)
+      }
+      "mkdir" => fs::create_dir_all(path).map(|_| RloxType::NullType),
+      "remove" => {
+        if path.is_dir() { fs::remove_dir(path) } else { fs::remove_file(path) }.map(|_| RloxType::NullType)
+      }
+      _ => Err(io::Error::other(format!("undefined property '{}'", self.name))),
+    }
+  }
+}
+
+impl Callable for FsFunction {
+  fn arity(&self) -> usize {
+    self.arity
+  }
+
+  fn call(&self, interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let path = match &arguments[0] {
+      RloxType::StringType(path) => path,
+      value => return Err(RloxError::InterpreterError(format!("fs.{}() expects a path string, not {}.", self.name, value.type_name()))),
+    };
+    let failed = |reason: String| RloxError::InterpreterError(format!("fs.{}('{}') failed: {}.", self.name, path, reason));
+
+    let resolved = interpreter.fs_policy().check(path, self.write).map_err(failed)?;
+    self.run(&resolved, &arguments).map_err(|e| failed(describe(e)))
+  }
+
+  fn name(&self) -> String {
+    format!("<native fs.{} function>", self.name)
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rlox-fs-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::canonicalize(dir).unwrap()
+  }
+
+  #[test]
+  fn test_policy_access() {
+    let dir = scratch("access");
+    let file = dir.join("data.txt");
+    let file = file.to_str().unwrap();
+
+    assert_eq!(FsPolicy::disabled().check(file, false), Err("file system access is disabled".to_string()));
+    assert_eq!(FsPolicy::read_only(vec![dir.clone()]).check(file, true), Err("file system is read-only".to_string()));
+    assert!(FsPolicy::read_only(vec![dir.clone()]).check(file, false).is_ok());
+    assert!(FsPolicy::read_write(vec![dir.clone()]).check(file, true).is_ok());
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_policy_roots() {
+    let dir = scratch("roots");
+    let inside = dir.join("inside");
+    fs::create_dir_all(&inside).unwrap();
+    let policy = FsPolicy::read_write(vec![inside.clone()]);
+
+    let path = |p: &Path| p.to_str().unwrap().to_string();
+    assert!(policy.check(&path(&inside.join("new/file.txt")), true).is_ok());
+    assert!(policy.check(&path(&dir.join("outside.txt")), false).is_err());
+    assert!(policy.check(&path(&inside.join("../outside.txt")), false).is_err());
+    assert!(policy.check(&path(&inside.join("missing/../../outside.txt")), false).is_err());
+
+    #[cfg(unix)]
+    {
+      std::os::unix::fs::symlink(&dir, inside.join("link")).unwrap();
+      assert!(policy.check(&path(&inside.join("link/outside.txt")), false).is_err());
+
+      std::os::unix::fs::symlink(dir.join("outside.txt"), inside.join("dangling")).unwrap();
+      assert!(policy.check(&path(&inside.join("dangling")), true).is_err());
+      assert!(policy.check(&path(&inside.join("dangling/file.txt")), true).is_err());
+    }
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_write_does_not_follow_symlinks() {
+    let dir = scratch("nofollow");
+    let target = dir.join("target.txt");
+    std::os::unix::fs::symlink(&target, dir.join("link")).unwrap();
+
+    assert!(open_for_write(&dir.join("link"), false).is_err());
+    assert!(open_for_write(&dir.join("link"), true).is_err());
+    assert!(!target.exists());
+    assert!(open_for_write(&dir.join("file.txt"), false).is_ok());
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,329 @@
+// Cycle collector for the objects kept alive by reference counting.
+//
+// Environments, instances and classes refer to each other through Rc, so
+// a cycle between them, like an instance holding one of its bound methods
+// or a closure stored in the scope it captures, is never dropped. Every
+// map or vector of values is registered with the heap. Collecting walks
+// all objects reachable from them and counts the references each object
+// gets from the other objects, whatever is left of its strong count comes
+// from outside the heap (the interpreter, values on the native stack) and
+// makes the object a root. Objects not reachable from any root are
+// garbage, and clearing their values breaks the cycles so Rc can drop them.
+use std::{
+  cell::RefCell,
+  collections::HashMap,
+  rc::{Rc, Weak},
+};
+use super::{
+  callable::Callable,
+  rlox_type::RloxType,
+  environment::Environment,
+  rlox_class::{RloxClassMethods, RloxSuperClass},
+};
+
+pub type Values = Rc<RefCell<HashMap<String, RloxType>>>;
+pub type Slots = Rc<RefCell<Vec<RloxType>>>;
+
+// Number of registered objects before the first automatic collection.
+const INITIAL_THRESHOLD: usize = 10_000;
+
+pub enum GcRef {
+  Values(Values),
+  Slots(Slots),
+  Environment(Rc<RefCell<Environment>>),
+  Methods(RloxClassMethods),
+  Superclass(RloxSuperClass),
+}
+
+// Implemented by everything holding references to heap objects.
+pub trait Trace {
+  fn trace(&self, refs: &mut Vec<GcRef>);
+}
+
+enum Tracked {
+  Values(Weak<RefCell<HashMap<String, RloxType>>>),
+  Slots(Weak<RefCell<Vec<RloxType>>>),
+}
+
+struct Heap {
+  objects: Vec<Tracked>,
+  threshold: usize,
+}
+
+thread_local! {
+  static HEAP: RefCell<Heap> = const {
+    RefCell::new(Heap {
+      objects: Vec::new(),
+      threshold: INITIAL_THRESHOLD,
+    })
+  };
+}
+
+impl Tracked {
+  fn is_alive(&self) -> bool {
+    match self {
+      Tracked::Values(v) => v.strong_count() > 0,
+      Tracked::Slots(s) => s.strong_count() > 0,
+    }
+  }
+
+  fn upgrade(&self) -> Option<GcRef> {
+    match self {
+      Tracked::Values(v) => v.upgrade().map(GcRef::Values),
+      Tracked::Slots(s) => s.upgrade().map(GcRef::Slots),
+    }
+  }
+}
+
+struct Node {
+  object: GcRef,
+  // None when the object couldn't be looked into.
+  edges: Option<Vec<usize>>,
+  internal: usize,
+  marked: bool,
+}
+
+impl GcRef {
+  fn id(&self) -> usize {
+    match self {
+      GcRef::Values(v) => Rc::as_ptr(v) as *const () as usize,
+      GcRef::Slots(s) => Rc::as_ptr(s) as *const () as usize,
+      GcRef::Environment(e) => Rc::as_ptr(e) as *const () as usize,
+      GcRef::Methods(m) => Rc::as_ptr(m) as *const () as usize,
+      GcRef::Superclass(s) => Rc::as_ptr(s) as *const () as usize,
+    }
+  }
+
+  fn strong_count(&self) -> usize {
+    match self {
+      GcRef::Values(v) => Rc::strong_count(v),
+      GcRef::Slots(s) => Rc::strong_count(s),
+      GcRef::Environment(e) => Rc::strong_count(e),
+      GcRef::Methods(m) => Rc::strong_count(m),
+      GcRef::Superclass(s) => Rc::strong_count(s),
+    }
+  }
+
+  // Objects borrowed mutably are being modified, they are treated as roots.
+  fn children(&self) -> Option<Vec<GcRef>> {
+    let mut refs = Vec::new();
+    match self {
+      GcRef::Values(v) => v.try_borrow().ok()?.values().for_each(|value| value.trace(&mut refs)),
+      GcRef::Slots(s) => s.try_borrow().ok()?.iter().for_each(|value| value.trace(&mut refs)),
+      GcRef::Environment(e) => e.try_borrow().ok()?.trace(&mut refs),
+      GcRef::Methods(m) => m.try_borrow().ok()?.values().for_each(|method| method.trace(&mut refs)),
+      GcRef::Superclass(s) => {
+        if let Some(superclass) = s.try_borrow().ok()?.as_ref() {
+          superclass.trace(&mut refs);
+        }
+      }
+    }
+    Some(refs)
+  }
+
+  // Drops the references held by the object, returns true for environments
+  // and instances.
+  fn clear(&self) -> bool {
+    match self {
+      GcRef::Values(v) => {
+        let values = match v.try_borrow_mut() {
+          Ok(mut values) => std::mem::take(&mut *values),
+          Err(_) => return false,
+        };
+        drop(values);
+        true
+      }
+      GcRef::Slots(s) => {
+        let slots = match s.try_borrow_mut() {
+          Ok(mut slots) => std::mem::take(&mut *slots),
+          Err(_) => return false,
+        };
+        drop(slots);
+        true
+      }
+      GcRef::Methods(m) => {
+        let methods = match m.try_borrow_mut() {
+          Ok(mut methods) => std::mem::take(&mut *methods),
+          Err(_) => return false,
+        };
+        drop(methods);
+        false
+      }
+      _ => false,
+    }
+  }
+}
+
+pub fn track_values(values: &Values) {
+  track(Tracked::Values(Rc::downgrade(values)));
+}
+
+pub fn track_slots(slots: &Slots) {
+  track(Tracked::Slots(Rc::downgrade(slots)));
+}
+
+// Registers a new object, collecting first when enough were registered
+// since the last collection.
+fn track(object: Tracked) {
+  let collect = HEAP.with(|heap| {
+    let mut heap = heap.borrow_mut();
+    heap.objects.push(object);
+    heap.objects.len() >= heap.threshold
+  });
+
+  if collect {
+    collect_garbage();
+  }
+}
+
+// Number of environments and instances alive.
+pub fn live_objects() -> usize {
+  HEAP.with(|heap| {
+    let mut heap = heap.borrow_mut();
+    heap.objects.retain(Tracked::is_alive);
+    heap.objects.len()
+  })
+}
+
+// Frees unreachable cycles, returns the number of environments and
+// instances collected.
+pub fn collect_garbage() -> usize {
+  let mut stack: Vec<GcRef> = HEAP.with(|heap| {
+    let mut heap = heap.borrow_mut();
+    heap.objects.retain(Tracked::is_alive);
+    heap.objects.iter().filter_map(Tracked::upgrade).collect()
+  });
+
+  // Only one reference to every object is kept while counting.
+  let mut nodes: HashMap<usize, Node> = HashMap::new();
+  while let Some(object) = stack.pop() {
+    let id = object.id();
+    if nodes.contains_key(&id) {
+      continue;
+    }
+
+    let edges = object.children().map(|children| {
+      children.into_iter().map(|child| {
+        let child_id = child.id();
+        if !nodes.contains_key(&child_id) {
+          stack.push(child);
+        }
+        child_id
+      }).collect()
+    });
+    nodes.insert(id, Node { object, edges, internal: 0, marked: false });
+  }
+
+  let edges: Vec<usize> = nodes.values().flat_map(|n| n.edges.iter().flatten().copied()).collect();
+  for id in edges {
+    if let Some(node) = nodes.get_mut(&id) {
+      node.internal += 1;
+    }
+  }
+
+  let mut gray: Vec<usize> = nodes.iter()
+    .filter(|(_, n)| n.edges.is_none() || n.object.strong_count() - 1 > n.internal)
+    .map(|(id, _)| *id)
+    .collect();
+  while let Some(id) = gray.pop() {
+    if let Some(node) = nodes.get_mut(&id) {
+      if !node.marked {
+        node.marked = true;
+        gray.extend(node.edges.iter().flatten());
+      }
+    }
+  }
+
+  let garbage: Vec<GcRef> = nodes.into_values().filter(|n| !n.marked).map(|n| n.object).collect();
+  let collected = garbage.iter().filter(|object| object.clear()).count();
+  drop(garbage);
+
+  HEAP.with(|heap| {
+    let mut heap = heap.borrow_mut();
+    heap.objects.retain(Tracked::is_alive);
+    heap.threshold = INITIAL_THRESHOLD.max(heap.objects.len() * 2);
+  });
+
+  collected
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::rlox::*;
+  use crate::scanners::Scanner;
+
+  fn run(input: &str) -> Result<RloxType, RloxError> {
+    let mut scanner = Scanner::new(input.chars().collect());
+    let statements = Parser::new(scanner.scan()?).parse()?;
+    let interpreter = Interpreter::new();
+    Resolver::new(interpreter.clone()).resolve_statements(statements.clone())?;
+
+    let mut final_result = RloxType::NullType;
+    for statement in statements {
+      final_result = interpreter.execute(statement)?;
+    }
+
+    Ok(final_result)
+  }
+
+  const CYCLES: &str = "
+    class Node { init() { this.me = this; this.method = this.get; } get() { return this.me; } }
+    fun closure() { var f; fun g() { return f; } f = g; }
+  ";
+
+  #[test]
+  fn test_collects_cycles() -> Result<(), RloxError> {
+    let before = live_objects();
+    run(&format!("{} for (var i = 0; i < 100; i = i + 1) {{ Node(); closure(); }}", CYCLES))?;
+
+    assert!(live_objects() >= before + 200);
+    assert!(collect_garbage() >= 200);
+    assert_eq!(live_objects(), before);
+    Ok(())
+  }
+
+  #[test]
+  fn test_memory_stays_bounded() -> Result<(), RloxError> {
+    let before = live_objects();
+    // Without collecting at least two objects leak on every iteration.
+    run(&format!("{} for (var i = 0; i < 30000; i = i + 1) {{ Node(); closure(); }}", CYCLES))?;
+
+    assert!(live_objects() - before < 3 * INITIAL_THRESHOLD);
+    Ok(())
+  }
+
+  #[test]
+  fn test_reachable_objects_are_kept() -> Result<(), RloxError> {
+    let result = run(&format!("{} var n = Node(); n.x = 1; var count = 0; fun counter() {{ fun inc() {{ count = count + 1; return count; }} return inc; }} var c = counter(); c(); gc(); c(); n.method().me.x + c();", CYCLES))?;
+    assert_eq!(result, RloxType::IntegerType(4));
+    Ok(())
+  }
+
+  #[test]
+  fn test_collects_list_cycles() -> Result<(), RloxError> {
+    let before = live_objects();
+    run("fun cycle() { var l = \"a\".split(\",\"); l.push(l); l.push(l.len); } for (var i = 0; i < 100; i = i + 1) cycle();")?;
+
+    assert!(collect_garbage() >= 100);
+    assert_eq!(live_objects(), before);
+    Ok(())
+  }
+
+  #[test]
+  fn test_collects_class_field_cycles() -> Result<(), RloxError> {
+    let before = live_objects();
+    run("fun cycle() { class Node { class ROOT = Node(); class make() { return Node(); } } Node.last = Node.make(); } for (var i = 0; i < 100; i = i + 1) cycle();")?;
+
+    assert!(collect_garbage() >= 100);
+    assert_eq!(live_objects(), before);
+    Ok(())
+  }
+
+  #[test]
+  fn test_gc_native() -> Result<(), RloxError> {
+    let result = run(&format!("{} for (var i = 0; i < 10; i = i + 1) closure(); gc();", CYCLES))?;
+    assert_eq!(result, RloxType::IntegerType(10));
+    Ok(())
+  }
+}
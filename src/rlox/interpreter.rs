@@ -9,11 +9,16 @@ use super::{
   rlox_function::RloxFunction,
   rlox_errors::RloxError,
   rlox_class::RloxClass,
+  callable::Callable,
+  numeric,
+  string_methods,
+  fs::FsPolicy,
 };
 use std::{
-  cell::RefCell,
+  cell::{Cell, RefCell},
   rc::Rc,
   collections::HashMap,
+  io::{BufRead, BufReader, Write},
 };
 
 #[derive(PartialEq, Eq, Hash)]
@@ -24,26 +29,103 @@ pub enum VarExpr {
   SuperExpr(Super),
 }
 
+impl VarExpr {
+  fn id(&self) -> uuid::Uuid {
+    match self {
+      VarExpr::VariableExpr(e) => e.id,
+      VarExpr::AssignmentExpr(e) => e.id,
+      VarExpr::ThisExpr(e) => e.id,
+      VarExpr::SuperExpr(e) => e.id,
+    }
+  }
+}
+
+// Resolved local variable, how many scopes up it was declared and its slot
+// in that scope.
+#[derive(Clone, Copy)]
+struct Location {
+  depth: usize,
+  slot: usize,
+}
+
 type Exp = Rc<RefCell<dyn Expr<RloxType>>>;
 type Stm = Rc<RefCell<dyn Stmt<RloxType>>>;
 
+fn compound_operator(operator: &Token) -> Option<TokenType> {
+  match operator.token_type {
+    TokenType::PLUSEQUAL | TokenType::PLUSPLUS => Some(TokenType::PLUS),
+    TokenType::MINUSEQUAL | TokenType::MINUSMINUS => Some(TokenType::MINUS),
+    TokenType::STAREQUAL => Some(TokenType::STAR),
+    TokenType::SLASHEQUAL => Some(TokenType::SLASH),
+    TokenType::PERCENTEQUAL => Some(TokenType::PERCENT),
+    _ => None,
+  }
+}
+
 #[derive(Clone)]
 pub struct Interpreter {
   environment: Rc<RefCell<Environment>>,
   globals: Rc<RefCell<Environment>>,
-  locals: Rc<RefCell<HashMap<VarExpr, usize>>>
+  locals: Rc<RefCell<HashMap<uuid::Uuid, Location>>>,
+  output: Rc<RefCell<dyn Write>>,
+  fs_policy: Rc<RefCell<FsPolicy>>,
+  input: Rc<RefCell<Rc<RefCell<dyn BufRead>>>>,
+  args: Rc<RefCell<Vec<String>>>,
+  line: Rc<Cell<usize>>,
 }
 
 impl Interpreter {
   pub fn new() -> Interpreter {
+    Interpreter::new_with_output(Rc::new(RefCell::new(std::io::stdout())))
+  }
+
+  // Everything printed by scripts goes to output.
+  pub fn new_with_output(output: Rc<RefCell<dyn Write>>) -> Interpreter {
     let env_init = Rc::new(RefCell::new(Environment::new()));
     Interpreter {
       environment: env_init.clone(),
       globals: Rc::new(RefCell::new(Environment::new())),
       locals: Rc::new(RefCell::new(HashMap::new())),
+      output,
+      fs_policy: Rc::new(RefCell::new(FsPolicy::default())),
+      input: Rc::new(RefCell::new(Rc::new(RefCell::new(BufReader::new(std::io::stdin()))))),
+      args: Rc::new(RefCell::new(Vec::new())),
+      line: Rc::new(Cell::new(0)),
     }
   }
 
+  // Line of the call being evaluated, natives use it in their errors.
+  pub fn line(&self) -> usize {
+    self.line.get()
+  }
+
+  // readLine and readAll read from input, stdin by default.
+  pub fn set_input(&self, input: Rc<RefCell<dyn BufRead>>) {
+    *self.input.borrow_mut() = input;
+  }
+
+  pub fn input(&self) -> Rc<RefCell<dyn BufRead>> {
+    self.input.borrow().clone()
+  }
+
+  // Command line arguments returned by args().
+  pub fn set_args(&self, args: Vec<String>) {
+    *self.args.borrow_mut() = args;
+  }
+
+  pub fn args(&self) -> Vec<String> {
+    self.args.borrow().clone()
+  }
+
+  // Decides which files the fs natives can read and write.
+  pub fn set_fs_policy(&self, policy: FsPolicy) {
+    *self.fs_policy.borrow_mut() = policy;
+  }
+
+  pub fn fs_policy(&self) -> FsPolicy {
+    self.fs_policy.borrow().clone()
+  }
+
   pub fn interpret(&self, statements: Vec<Stm>, callback: Option<fn(resutl: Result<RloxType, RloxError>)>) {
     for statement in statements {
       let result = self.evaluate_stmt(statement);
@@ -53,6 +135,19 @@ impl Interpreter {
     }
   }
 
+  pub fn globals(&self) -> Vec<(String, RloxType)> {
+    self.globals.borrow().values()
+  }
+
+  pub fn execute(&self, stmt: Stm) -> Result<RloxType, RloxError> {
+    self.evaluate_stmt(stmt)
+  }
+
+  pub fn run_test(&self, test: &Test<RloxType>) -> Result<RloxType, RloxError> {
+    let env = Environment::new_with_parent(self.environment.borrow().clone());
+    self.execute_block(test.body.clone(), env)
+  }
+
   fn evaluate_expr(&self, expr: Exp) -> Result<RloxType, RloxError> {
     expr.borrow().accept(Rc::new(RefCell::new(self.clone())))
   }
@@ -61,6 +156,31 @@ impl Interpreter {
     stmt.borrow().accept(Rc::new(RefCell::new(self.clone())))
   }
 
+  // Evaluates callee and arguments of a call, checking the callee can be
+  // called with them.
+  fn evaluate_call(&self, expr: &Call<RloxType>) -> Result<(Box<dyn Callable>, Vec<RloxType>), RloxError> {
+    let callee = self.evaluate_expr(expr.callee.clone())?;
+
+    let mut arguments = Vec::new();
+    for argument in expr.arguments.clone() {
+      arguments.push(self.evaluate_expr(argument)?);
+    }
+    self.line.set(expr.parent.line);
+
+    match callee {
+      RloxType::CallableType(c) => {
+        if c.variadic() && arguments.len() < c.arity() {
+          return Err(RloxError::InterpreterError(format!("Expected at least {} arguments but got {}.", c.arity(), arguments.len())))
+        }
+        if !c.variadic() && arguments.len() != c.arity() {
+          return Err(RloxError::InterpreterError(format!("Expected {} arguments but got {}.", c.arity(), arguments.len())))
+        }
+        Ok((c, arguments))
+      }
+      _ => Err(RloxError::InterpreterError("Can only call functions and classes.".to_string()))
+    }
+  }
+
   fn is_truthy(&self, rlox_type: RloxType) -> Result<RloxType, RloxError> {
     match rlox_type {
       RloxType::NullType => Ok(RloxType::BooleanType(false)),
@@ -89,22 +209,14 @@ impl Interpreter {
   }
 
   fn compute_binary_operand(&self, token_type: &TokenType, left: Literal, right: Literal) -> Result<RloxType, RloxError> {
-    if let RloxType::NumberType(left_number) = left {
-      if let RloxType::NumberType(right_number) = right {
-        return match token_type {
-          TokenType::MINUS => Ok(RloxType::NumberType(left_number - right_number)),
-          TokenType::PLUS => Ok(RloxType::NumberType(left_number + right_number)),
-          TokenType::SLASH => Ok(RloxType::NumberType(left_number / right_number)),
-          TokenType::STAR => Ok(RloxType::NumberType(left_number * right_number)),
-          TokenType::GREATER => Ok(RloxType::BooleanType(left_number > right_number)),
-          TokenType::GREATEREQUAL => Ok(RloxType::BooleanType(left_number >= right_number)),
-          TokenType::LESS => Ok(RloxType::BooleanType(left_number < right_number)),
-          TokenType::LESSEQUAL => Ok(RloxType::BooleanType(left_number <= right_number)),
-          TokenType::BANGEQUAL => self.not(self.is_equal(left, right)?),
-          TokenType::EQUALEQUAL => self.is_equal(left, right),
-          _ => Err(RloxError::InterpreterError(format!("unimplemented operand {}", token_type.name()))),
-        }
-      }
+    match token_type {
+      TokenType::BANGEQUAL => return self.not(self.is_equal(left, right)?),
+      TokenType::EQUALEQUAL => return self.is_equal(left, right),
+      _ => (),
+    }
+
+    if numeric::is_number(&left) && numeric::is_number(&right) {
+      return numeric::binary(token_type, &left, &right);
     }
 
     if let RloxType::StringType(left_number) = left {
@@ -119,6 +231,27 @@ impl Interpreter {
     return Err(RloxError::InterpreterError(format!("unsupported operand type(s) for {}: both operand types must be number", token_type.name())));
   }
 
+  // Text of a value in an interpolated string, instances can define
+  // toString() to control theirs.
+  fn stringify(&self, value: RloxType) -> Result<String, RloxError> {
+    if let RloxType::ClassType(instance) = &value {
+      if let Some(to_string) = instance.method("toString") {
+        return Ok(to_string.call(self, Vec::new())?.to_string());
+      }
+    }
+
+    Ok(value.to_string())
+  }
+
+  // The value stored by an assignment, for compound assignments and
+  // increments the operator applied to the current value.
+  fn compound_value(&self, operator: &Token, current: Option<RloxType>, value: RloxType) -> Result<RloxType, RloxError> {
+    match (compound_operator(operator), current) {
+      (Some(token_type), Some(current)) => self.compute_binary_operand(&token_type, current, value),
+      _ => Ok(value),
+    }
+  }
+
   pub fn execute_block(&self, statements: Vec<Stm>, environment: Environment)-> Result<RloxType, RloxError> {
     let previous = self.environment.replace(environment);
 
@@ -133,13 +266,17 @@ impl Interpreter {
     Ok(RloxType::NullType)
   }
 
-  pub fn resolve(&self, var_expr: VarExpr, depth: usize) {
-    self.locals.borrow_mut().insert(var_expr, depth);
+  pub fn resolve(&self, var_expr: VarExpr, depth: usize, slot: usize) {
+    self.locals.borrow_mut().insert(var_expr.id(), Location { depth, slot });
+  }
+
+  fn location(&self, id: &uuid::Uuid) -> Option<Location> {
+    self.locals.borrow().get(id).copied()
   }
 
-  fn lookup_variable(&self, name: Token, expr: &VarExpr) -> Result<RloxType, RloxError> {
-    match self.locals.borrow().get(expr) {
-      Some(distance) => self.environment.borrow().get_at(*distance, &name.lexeme),
+  fn lookup_variable(&self, name: &Token, id: &uuid::Uuid) -> Result<RloxType, RloxError> {
+    match self.location(id) {
+      Some(location) => self.environment.borrow().get_at(location.depth, location.slot),
       None => self.globals.borrow().get(&name.lexeme),
     }
   }
@@ -157,23 +294,78 @@ impl Interpreter {
         return Err(RloxError::InterpreterError("Expected method.".to_string()));
       }
     }
-    Ok(RloxClass::new(&stmt.name.lexeme, superclass, Rc::new(RefCell::new(methods))))
+    let klass = RloxClass::new(&stmt.name.lexeme, superclass, Rc::new(RefCell::new(methods)));
+
+    for method in &stmt.class_methods {
+      if let Some(func_method) = method.borrow().as_any().downcast_ref::<Function<RloxType>>() {
+        klass.add_class_method(&func_method.name.lexeme, RloxFunction::new(func_method, env, false));
+      } else {
+        return Err(RloxError::InterpreterError("Expected method.".to_string()));
+      }
+    }
+
+    for (accessors, is_setter) in [(&stmt.getters, false), (&stmt.setters, true)] {
+      for accessor in accessors {
+        if let Some(func_method) = accessor.borrow().as_any().downcast_ref::<Function<RloxType>>() {
+          klass.add_accessor(&func_method.name.lexeme, RloxFunction::new(func_method, env, false), is_setter);
+        } else {
+          return Err(RloxError::InterpreterError("Expected method.".to_string()));
+        }
+      }
+    }
+    Ok(klass)
+  }
+
+  // Assignment to a field of an instance or class, compound assignments
+  // read the field first.
+  fn assign_property(
+    &self,
+    expr: &Set<RloxType>,
+    get: impl Fn(&Token) -> Result<RloxType, RloxError>,
+    set: impl Fn(&Token, &RloxType) -> Result<(), RloxError>,
+  ) -> Result<RloxType, RloxError> {
+    let current = match compound_operator(&expr.operator) {
+      Some(_) => Some(get(&expr.name)?),
+      None => None,
+    };
+    let value = self.evaluate_expr(expr.value.clone())?;
+    let value = self.compound_value(&expr.operator, current.clone(), value)?;
+    set(&expr.name, &value)?;
+
+    match (expr.postfix, current) {
+      (true, Some(current)) => Ok(current),
+      _ => Ok(value),
+    }
   }
 
-  fn process_klass(&self, stmt: &Class<RloxType>, superklass: Option<RloxClass>, env: &Environment) -> Result<(), RloxError> {
+  // Class fields are evaluated in order once the class is defined, so
+  // they can hold instances of it.
+  fn init_class_fields(&self, stmt: &Class<RloxType>, klass: &RloxClass) -> Result<(), RloxError> {
+    for field in &stmt.fields {
+      if let Some(var) = field.borrow().as_any().downcast_ref::<Var<RloxType>>() {
+        let value = self.evaluate_expr(var.initializer.clone())?;
+        klass.set_field(&var.name.lexeme, value);
+      } else {
+        return Err(RloxError::InterpreterError("Expected class field.".to_string()));
+      }
+    }
+    Ok(())
+  }
+
+  // Methods share the values of the environment they close over, so the
+  // class defined after them is still visible inside them.
+  fn process_klass(&self, stmt: &Class<RloxType>, superklass: Option<RloxClass>, env: &Environment) -> Result<RloxClass, RloxError> {
     if let Some(sk) = superklass.clone() {
-      env.define(stmt.name.lexeme.clone(), RloxType::NullType);
       let environment = Environment::new_with_parent(env.clone());
       environment.define("super".to_string(), RloxType::CallableType(Box::new(sk.clone())));
       let klass = self.prepare_klass(stmt, Some(sk.clone()), &environment)?;
-      env.assign(&stmt.name.lexeme, Literal::CallableType(Box::new(klass)))?;
+      env.define(stmt.name.lexeme.clone(), Literal::CallableType(Box::new(klass.clone())));
+      Ok(klass)
     } else {
-      env.define(stmt.name.lexeme.clone(), RloxType::NullType);
       let klass = self.prepare_klass(stmt, superklass, env)?;
-      env.assign(&stmt.name.lexeme, Literal::CallableType(Box::new(klass)))?;
+      env.define(stmt.name.lexeme.clone(), Literal::CallableType(Box::new(klass.clone())));
+      Ok(klass)
     }
-
-    Ok(())
   }
 }
 
@@ -207,7 +399,7 @@ impl super::stmt::Visitor<RloxType> for Interpreter {
 
   fn visit_print_stmt(&self, stmt: &Print<RloxType>) -> Result<RloxType, RloxError> {
     let value = self.evaluate_expr(stmt.expression.clone())?;
-    println!("{}", value);
+    writeln!(self.output.borrow_mut(), "{}", value)?;
     Ok(RloxType::NullType)
   }
 
@@ -238,11 +430,26 @@ impl super::stmt::Visitor<RloxType> for Interpreter {
   }
 
   fn visit_return_stmt(&self, stmt: &Return<RloxType>) -> Result<RloxType, RloxError> {
+    // A call in tail position is handed back to the calling RloxFunction,
+    // which runs it in place of the current frame.
+    if let Some(call) = stmt.value.borrow().as_any().downcast_ref::<Call<RloxType>>() {
+      let (callee, arguments) = self.evaluate_call(call)?;
+      if let Some(function) = callee.as_any().downcast_ref::<RloxFunction>() {
+        return Err(RloxError::TailCall(function.clone(), arguments));
+      }
+      return Err(RloxError::ReturnValue(callee.call(self, arguments)?));
+    }
+
     let value = self.evaluate_expr(stmt.value.clone())?;
 
     Err(RloxError::ReturnValue(value))
   }
 
+  fn visit_test_stmt(&self, _: &Test<RloxType>) -> Result<RloxType, RloxError> {
+    // Tests only run under the test runner, see Interpreter::run_test.
+    Ok(RloxType::NullType)
+  }
+
   fn visit_class_stmt(&self, stmt: &Class<RloxType>) -> Result<RloxType, RloxError> {
     let mut superklass: Option<RloxClass> = None;
     if let Some(super_class) = stmt.superclass.clone() {
@@ -260,23 +467,26 @@ impl super::stmt::Visitor<RloxType> for Interpreter {
       }
     }
 
-    let env = self.environment.borrow();
+    let klass = {
+      let env = self.environment.borrow();
 
-    match env.is_top_level() {
-      true => {
-        let globals = self.globals.borrow();
-        // globals.define(stmt.name.lexeme.clone(), RloxType::NullType);
-        // let klass = self.prepare_klass(stmt, superklass, &globals)?;
-        // globals.assign(&stmt.name.lexeme, Literal::CallableType(Box::new(klass)))?;
-        self.process_klass(stmt, superklass, &globals)?;
-      }
-      false => {
-        // env.define(stmt.name.lexeme.clone(), RloxType::NullType);
-        // let klass = self.prepare_klass(stmt, superklass, &env)?;
-        // env.assign(&stmt.name.lexeme, Literal::CallableType(Box::new(klass)))?;
-        self.process_klass(stmt, superklass, &env)?;
+      match env.is_top_level() {
+        true => {
+          let globals = self.globals.borrow();
+          // globals.define(stmt.name.lexeme.clone(), RloxType::NullType);
+          // let klass = self.prepare_klass(stmt, superklass, &globals)?;
+          // globals.assign(&stmt.name.lexeme, Literal::CallableType(Box::new(klass)))?;
+          self.process_klass(stmt, superklass, &globals)?
+        }
+        false => {
+          // env.define(stmt.name.lexeme.clone(), RloxType::NullType);
+          // let klass = self.prepare_klass(stmt, superklass, &env)?;
+          // env.assign(&stmt.name.lexeme, Literal::CallableType(Box::new(klass)))?;
+          self.process_klass(stmt, superklass, &env)?
+        }
       }
-    }
+    };
+    self.init_class_fields(stmt, &klass)?;
 
     Ok(RloxType::NullType)
   }
@@ -290,8 +500,8 @@ impl super::expr::Visitor<RloxType> for Interpreter {
     self.compute_binary_operand(&expr.operator.token_type, left, right)
   }
 
-  fn visit_grouping_expr(&self, _: &Grouping<RloxType>) -> Result<RloxType, RloxError> {
-    Ok(RloxType::NullType)
+  fn visit_grouping_expr(&self, expr: &Grouping<RloxType>) -> Result<RloxType, RloxError> {
+    self.evaluate_expr(expr.expression.clone())
   }
 
   fn visit_literal_expr(&self, expr: &LiteralObj) -> Result<RloxType, RloxError> {
@@ -305,35 +515,44 @@ impl super::expr::Visitor<RloxType> for Interpreter {
     let right = self.evaluate_expr(expr.right.clone())?;
 
     match expr.operator.token_type {
-      TokenType::MINUS => {
-        if let RloxType::NumberType(n) = right {
-          return Ok(RloxType::NumberType(-1.0 * n));
-        }
-        return Err(RloxError::InterpreterError("Invalid type".to_string()));
-      }
+      TokenType::MINUS => numeric::negate(&right),
+      TokenType::TILDE => numeric::invert(&right),
       TokenType::BANG => self.is_truthy(right),
       _ => Err(RloxError::InterpreterError("unsupported operand".to_string())),
     }
   }
 
   fn visit_variable_expr(&self, expr: &Variable) -> Result<RloxType, RloxError> {
-    // self.environment.borrow().get(&expr.name.lexeme)
-    self.lookup_variable(expr.name.clone(), &VarExpr::VariableExpr(expr.clone()))
+    self.lookup_variable(&expr.name, &expr.id)
   }
 
   fn visit_assign_expr(&self, expr: &Assign<RloxType>) -> Result<RloxType, RloxError> {
+    let current = match compound_operator(&expr.operator) {
+      Some(_) => Some(self.lookup_variable(&expr.name, &expr.id)?),
+      None => None,
+    };
     let value = self.evaluate_expr(expr.value.clone())?;
-    match self.locals.borrow().get(&VarExpr::AssignmentExpr(expr.clone())) {
-      Some(distance) => self.environment.borrow().assign_at(*distance, &expr.name.lexeme, value.clone())?,
+    let value = self.compound_value(&expr.operator, current.clone(), value)?;
+
+    match self.location(&expr.id) {
+      Some(location) => self.environment.borrow().assign_at(location.depth, location.slot, value.clone())?,
       None => self.globals.borrow().assign(&expr.name.lexeme, value.clone())?,
     }
-    Ok(value)
+
+    match (expr.postfix, current) {
+      (true, Some(current)) => Ok(current),
+      _ => Ok(value),
+    }
   }
 
   fn visit_logical_expr(&self, expr: &Logical<RloxType>) -> Result<RloxType, RloxError> {
     let left = self.evaluate_expr(expr.left.clone())?;
 
-    if expr.operator.token_type == TokenType::OR {
+    if expr.operator.token_type == TokenType::QUESTIONQUESTION {
+      if left != RloxType::NullType {
+        return Ok(left);
+      }
+    } else if expr.operator.token_type == TokenType::OR {
       if self.is_truthy(left.clone())? == Literal::BooleanType(true) {
         return Ok(left.clone());
       }
@@ -346,31 +565,53 @@ impl super::expr::Visitor<RloxType> for Interpreter {
     Ok(self.evaluate_expr(expr.right.clone())?)
   }
 
-  fn visit_call_expr(&self, expr: &Call<RloxType>) -> Result<RloxType, RloxError> {
-    let callee = self.evaluate_expr(expr.callee.clone())?;
+  fn visit_interpolation_expr(&self, expr: &Interpolation<RloxType>) -> Result<RloxType, RloxError> {
+    let mut text = String::new();
 
-    let mut arguments = Vec::new();
-    for argument in expr.arguments.clone() {
-      arguments.push(self.evaluate_expr(argument)?);
+    for part in expr.parts.clone() {
+      let value = self.evaluate_expr(part)?;
+      text.push_str(&self.stringify(value)?);
     }
 
-    match callee {
-      RloxType::CallableType(c) => {
-        if arguments.len() != c.arity() {
-          return Err(RloxError::InterpreterError(format!("Expected {} arguments but got {}.", c.arity(), arguments.len())))
-        }
-        Ok(c.call(self, arguments)?)
-      }
-      _ => Err(RloxError::InterpreterError("Can only call functions and classes.".to_string()))
+    Ok(RloxType::StringType(text))
+  }
+
+  fn visit_conditional_expr(&self, expr: &Conditional<RloxType>) -> Result<RloxType, RloxError> {
+    let condition = self.evaluate_expr(expr.condition.clone())?;
+
+    if self.is_truthy(condition)? == Literal::BooleanType(true) {
+      self.evaluate_expr(expr.then_branch.clone())
+    } else {
+      self.evaluate_expr(expr.else_branch.clone())
+    }
+  }
+
+  fn visit_optional_chain_expr(&self, expr: &OptionalChain<RloxType>) -> Result<RloxType, RloxError> {
+    match self.evaluate_expr(expr.expression.clone()) {
+      Err(RloxError::NilChain) => Ok(RloxType::NullType),
+      result => result,
     }
   }
 
+  fn visit_call_expr(&self, expr: &Call<RloxType>) -> Result<RloxType, RloxError> {
+    let (callee, arguments) = self.evaluate_call(expr)?;
+    callee.call(self, arguments)
+  }
+
   fn visit_get_expr(&self, expr: &Get<RloxType>) -> Result<RloxType, RloxError> {
     let object = self.evaluate_expr(expr.object.clone())?;
 
     match object {
+      RloxType::NullType if expr.optional => Err(RloxError::NilChain),
+      RloxType::StringType(s) => string_methods::get(&s, &expr.name),
+      RloxType::ListType(list) => list.get(&expr.name),
+      RloxType::NativeType(native) => native.get(&expr.name),
+      RloxType::CallableType(c) => match c.as_any().downcast_ref::<RloxClass>() {
+        Some(klass) => klass.get(&expr.name),
+        None => Err(RloxError::InterpreterError("Only instances have properties.".to_string())),
+      },
       RloxType::ClassType(instance) => {
-        instance.get(&expr.name)
+        instance.get(self, &expr.name)
       }
       _ => Err(RloxError::InterpreterError("Only instances have properties.".to_string()))
     }
@@ -379,29 +620,31 @@ impl super::expr::Visitor<RloxType> for Interpreter {
   fn visit_set_expr(&self, expr: &Set<RloxType>) -> Result<RloxType, RloxError> {
     let object = self.evaluate_expr(expr.object.clone())?;
 
-    match object {
+    match &object {
       RloxType::ClassType(instance) => {
-        let value = self.evaluate_expr(expr.value.clone())?;
-        instance.set(&expr.name, &value)?;
-        Ok(value)
+        self.assign_property(expr, |name| instance.get(self, name), |name, value| instance.set(self, name, value))
       }
+      RloxType::CallableType(c) => match c.as_any().downcast_ref::<RloxClass>() {
+        Some(klass) => self.assign_property(expr, |name| klass.get(name), |name, value| klass.set(name, value)),
+        None => Err(RloxError::InterpreterError("Only instances have flields.".to_string())),
+      },
       _ => Err(RloxError::InterpreterError("Only instances have flields.".to_string()))
     }
   }
 
   fn visit_this_expr(&self, expr: &This) -> Result<RloxType, RloxError> {
-    self.lookup_variable(expr.keyword.clone(), &VarExpr::ThisExpr(expr.clone()))
+    self.lookup_variable(&expr.keyword, &expr.id)
   }
 
   fn visit_super_expr(&self, expr: &Super) -> Result<RloxType, RloxError> {
-    let distance = match self.locals.borrow().get(&VarExpr::SuperExpr(expr.clone())) {
-      Some(d) => d.clone(),
+    let distance = match self.location(&expr.id) {
+      Some(location) => location.depth,
       None => return Err(RloxError::InterpreterError("Interpreter internal error accessing 'super'.".to_string())),
     };
 
     let environment = self.environment.borrow();
 
-    let superclass = match environment.get_at(distance, "super")? {
+    let superclass = match environment.get_at(distance, 0)? {
       RloxType::CallableType(ct) => {
         if let Some(s) = ct.as_any().downcast_ref::<RloxClass>() {
           s.clone()
@@ -412,11 +655,18 @@ impl super::expr::Visitor<RloxType> for Interpreter {
       _ => return Err(RloxError::InterpreterError("Internal interpreter error, expected RloxClass type but received different type.".to_string())),
     };
 
-    let object = match environment.get_at(distance - 1, "this")? {
+    let object = match environment.get_at(distance - 1, 0)? {
       RloxType::ClassType(instance) => instance,
       _ => return Err(RloxError::ResolverError("'this' must be an instance of a class.".to_string())),
     };
 
+    drop(environment);
+
+    // super.x of a getter runs the inherited getter on this.
+    if let Some(getter) = superclass.find_accessor(&expr.method.lexeme, false) {
+      return getter.bind(&object).call(self, Vec::new());
+    }
+
     let method = superclass.find_method(&expr.method.lexeme)?;
     let rlox_func = method.bind(&object);
     Ok(RloxType::CallableType(Box::new(rlox_func)))
@@ -431,13 +681,16 @@ mod tests {
   use std::collections::HashMap;
 
   fn run(input: &str) -> Result<RloxType, RloxError> {
+    run_in(&Interpreter::new(), input)
+  }
+
+  fn run_in(interpreter: &Interpreter, input: &str) -> Result<RloxType, RloxError> {
     let data = input.chars().collect();
 
     let mut scanner = Scanner::new(data);
-    let tokens = scanner.scan_tokens();
+    let tokens = scanner.scan()?;
     let parser = Parser::new(tokens);
     let statements = parser.parse()?;
-    let interpreter = Interpreter::new();
 
     let resolver = Resolver::new(interpreter.clone());
     resolver.resolve_statements(statements.clone())?;
@@ -499,6 +752,51 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_equality_of_any_types() -> Result<(), RloxError> {
+    let test_input: HashMap<&str, bool> = [
+      ("\"a\" == \"a\";", true),
+      ("\"a\" != \"b\";", true),
+      ("\"a\" == nil;", false),
+      ("nil == nil;", true),
+      ("true != false;", true),
+      ("1 == \"1\";", false),
+      ("fun make() { fun f() { return 1; } return f; } make() == make();", false),
+      ("fun make() { fun f() { return 1; } return f; } var f = make(); f == f;", true),
+      ("fun g() {} fun outer() { fun g() {} return g; } g == outer();", false),
+      ("class A {} class B {} A == B;", false),
+      ("class A {} A == A;", true),
+      ("clock == clock;", true),
+    ].iter().cloned().collect();
+
+    for (&input, &expected_result) in test_input.iter() {
+      let val = run(input)?;
+      assert_eq!(val.to_string(), RloxType::BooleanType(expected_result).to_string());
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_inspect() -> Result<(), RloxError> {
+    let test_input: HashMap<&str, &str> = [
+      ("inspect(\"hi\");", "\"hi\""),
+      ("inspect(2.5);", "2.5"),
+      ("inspect(nil);", "nil"),
+      ("fun add(a, b) { return a + b; } inspect(add);", "<fn add/2>"),
+      ("class Point {} inspect(Point);", "<class Point>"),
+      ("class Point {} var p = Point(); p.y = \"b\"; p.x = 1; inspect(p);", "Point { x: 1, y: \"b\" }"),
+      ("class Node {} var n = Node(); n.next = n; inspect(n);", "Node { next: Node { ... } }"),
+    ].iter().cloned().collect();
+
+    for (&input, &expected_result) in test_input.iter() {
+      let val = run(input)?;
+      assert_eq!(val.to_string(), expected_result);
+    }
+
+    Ok(())
+  }
+
   #[test]
   fn test_global_vars() -> Result<(), RloxError> {
     let test_input: HashMap<&str, f64> = [
@@ -707,4 +1005,223 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn test_integers() -> Result<(), RloxError> {
+    let test_input: HashMap<&str, &str> = [
+      ("9007199254740993 + 2;", "9007199254740995"),
+      ("7 / 2;", "3"),
+      ("-7 / 2;", "-3"),
+      ("7.0 / 2;", "3.5"),
+      ("1 == 1.0;", "true"),
+      ("2 > 1.5;", "true"),
+      ("var id = 9223372036854775807; id;", "9223372036854775807"),
+    ].iter().cloned().collect();
+
+    for (&input, &expected_result) in test_input.iter() {
+      let val = run(input)?;
+      assert_eq!(val.to_string(), expected_result, "{}", input);
+    }
+
+    assert_eq!(run("9223372036854775807 + 1;").unwrap_err().to_string(), "Integer overflow.");
+    assert_eq!(run("1 / 0;").unwrap_err().to_string(), "Division by zero.");
+    Ok(())
+  }
+
+  #[test]
+  fn test_operator_precedence() -> Result<(), RloxError> {
+    let test_input: HashMap<&str, &str> = [
+      ("(1 + 2) * 3;", "9"),
+      ("10 % 4 * 2;", "4"),
+      ("7 div 2 + 1;", "4"),
+      ("-2 ** 2;", "-4"),
+      ("2 ** 3 ** 2;", "512"),
+      ("2 * 3 ** 2;", "18"),
+      ("1 + 6 & 3;", "3"),
+      ("1 | 2 ^ 3 & 4;", "3"),
+      ("1 << 2 & 7;", "4"),
+      ("~1 + 1;", "-1"),
+      ("var x = 0; for (var i = 0; i < 10; i = i + 1) { if (i % 2 == 0) x = x + i; } x;", "20"),
+    ].iter().cloned().collect();
+
+    for (&input, &expected_result) in test_input.iter() {
+      let val = run(input)?;
+      assert_eq!(val.to_string(), expected_result, "{}", input);
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_compound_assignment() -> Result<(), RloxError> {
+    let test_input: HashMap<&str, &str> = [
+      ("var x = 1; x += 2; x *= 5; x -= 1; x /= 2; x %= 4; x;", "3"),
+      ("var x = 1; x++;", "1"),
+      ("var x = 1; x++; x;", "2"),
+      ("var x = 1; ++x;", "2"),
+      ("var x = 1; x--; --x;", "-1"),
+      ("var s = \"a\"; s += \"b\"; s;", "ab"),
+      ("fun f() { var a = 10; a += 1; a++; return a; } f();", "12"),
+      ("fun f() { var a = 1; fun g() { a *= 3; } g(); g(); return a; } f();", "9"),
+      ("class C {} var c = C(); c.n = 1; c.n += 2; c.n++; c.n;", "4"),
+      ("class C {} var c = C(); c.n = 1; c.n++;", "1"),
+      ("class C {} var c = C(); c.n = 1; ++c.n;", "2"),
+      ("class C {} var c = C(); c.n = 0; var calls = 0; fun get() { calls++; return c; } get().n += 5; get().n++; --get().n; calls;", "3"),
+    ].iter().cloned().collect();
+
+    for (&input, &expected_result) in test_input.iter() {
+      let val = run(input)?;
+      assert_eq!(val.to_string(), expected_result, "{}", input);
+    }
+
+    assert_eq!(run("1++;").unwrap_err().to_string(), "++ Invalid assignment target.");
+    assert_eq!(run("var x = 1; x += \"a\";").unwrap_err().to_string(), "unsupported operand type(s) for PLUS: both operand types must be number");
+    Ok(())
+  }
+
+  #[test]
+  fn test_conditional_operators() -> Result<(), RloxError> {
+    let nodes = "class Node { init(next) { this.next = next; this.value = 1; } get() { return this.value; } } var n = Node(Node(nil));";
+    let test_input: HashMap<String, &str> = [
+      ("true ? 1 : 2;".to_string(), "1"),
+      ("nil ? 1 : false ? 2 : 3;".to_string(), "3"),
+      ("var x = 1 > 0 ? \"yes\" : \"no\"; x;".to_string(), "yes"),
+      ("nil ?? 1;".to_string(), "1"),
+      ("0 ?? 1;".to_string(), "0"),
+      ("false ?? 1;".to_string(), "false"),
+      ("nil ?? nil ?? 2;".to_string(), "2"),
+      ("var calls = 0; fun f() { calls++; return 1; } 5 ?? f(); true ? 1 : f(); calls;".to_string(), "0"),
+      (format!("{} n?.next?.value;", nodes), "1"),
+      (format!("{} n.next.next?.value;", nodes), "null"),
+      (format!("{} n.next.next?.next.value;", nodes), "null"),
+      (format!("{} n.next.next?.get();", nodes), "null"),
+      (format!("{} n?.get();", nodes), "1"),
+      (format!("{} n.next.next?.value ?? 42;", nodes), "42"),
+    ].iter().cloned().collect();
+
+    for (input, &expected_result) in test_input.iter() {
+      let val = run(input)?;
+      assert_eq!(val.to_string(), expected_result, "{}", input);
+    }
+
+    assert_eq!(run("var a = nil; a.b;").unwrap_err().to_string(), "Only instances have properties.");
+    assert_eq!(run("var a = nil; a?.b = 1;").unwrap_err().to_string(), "= Invalid assignment target.");
+    Ok(())
+  }
+
+  #[test]
+  fn test_string_interpolation() -> Result<(), RloxError> {
+    let test_input: HashMap<&str, &str> = [
+      ("var name = \"world\"; \"Hello ${name}!\";", "Hello world!"),
+      ("\"1 + 2 = ${1 + 2}\";", "1 + 2 = 3"),
+      ("\"${nil} ${true} ${1.5} ${7n}\";", "null true 1.5 7"),
+      ("var name = \"x\"; \"a ${\"b ${name}\"} c\";", "a b x c"),
+      ("\"\\${name}\";", "${name}"),
+      ("fun f() { var n = 5; return \"n is ${n * 2}\"; } f();", "n is 10"),
+      ("class P { init(x) { this.x = x; } toString() { return \"P(${this.x})\"; } } \"${P(3)}\";", "P(3)"),
+      ("class Q {} \"${Q()}\";", "Q instance"),
+    ].iter().cloned().collect();
+
+    for (&input, &expected_result) in test_input.iter() {
+      let val = run(input)?;
+      assert_eq!(val, RloxType::StringType(expected_result.to_string()), "{}", input);
+    }
+
+    assert_eq!(run("\"${1 2}\";").unwrap_err().to_string(), "Expect '}' after interpolated expression.");
+    Ok(())
+  }
+
+  #[test]
+  fn test_string_methods() -> Result<(), RloxError> {
+    let test_input: HashMap<&str, &str> = [
+      ("\" padded \".trim().upper();", "\"PADDED\""),
+      ("var upper = \"abc\".upper; upper();", "\"ABC\""),
+      ("\"a b c\".split(\" \").len();", "3"),
+      ("var parts = \"k=v\".split(\"=\"); parts.get(0) + \":\" + parts.get(1);", "\"k:v\""),
+      ("\"${\"ab\".repeat(2).len()}\";", "\"4\""),
+      ("\"x\".split(\",\") == \"x\".split(\",\");", "true"),
+      ("var l = \"a\".chars(); var m = l; m.push(\"b\"); l;", "[\"a\", \"b\"]"),
+    ].iter().cloned().collect();
+
+    for (&input, &expected_result) in test_input.iter() {
+      let val = run(input)?;
+      assert_eq!(val.inspect(), expected_result, "{}", input);
+    }
+
+    assert_eq!(run("\"abc\".size();").unwrap_err().to_string(), "Undefined property 'size' for string.");
+    assert_eq!(run("\"abc\".chars().get(3);").unwrap_err().to_string(), "Index 3 out of bounds for list of length 3.");
+    assert_eq!(run("1.len;").unwrap_err().to_string(), "Only instances have properties.");
+    Ok(())
+  }
+
+  #[test]
+  fn test_local_slots() -> Result<(), RloxError> {
+    let test_input: HashMap<&str, f64> = [
+      ("fun f() { var a = 1; var b = 2; { var a = 10; b = a + b; } return a + b; } f();", 13.0),
+      ("fun f(x, y) { var z = x * y; fun g(w) { return w + x + z; } return g(y); } f(2, 3);", 11.0),
+      ("fun f() { class Node { init(n) { this.n = n; } next() { return Node(this.n + 1); } } return Node(1).next().next().n; } f();", 3.0),
+      ("fun f() { var a = 1; fun inc() { a = a + 1; } inc(); inc(); return a; } f();", 3.0),
+    ].iter().cloned().collect();
+
+    for (&input, &expected_result) in test_input.iter() {
+      let val = run(input)?;
+      assert_eq!(val, RloxType::NumberType(expected_result), "{}", input);
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_tail_calls() -> Result<(), RloxError> {
+    let test_input: HashMap<&str, &str> = [
+      ("fun count(n, acc) { if (n == 0) return acc; return count(n - 1, acc + 1); } count(1000000, 0);", "1000000"),
+      ("fun isEven(n) { if (n == 0) return true; return isOdd(n - 1); } fun isOdd(n) { if (n == 0) return false; return isEven(n - 1); } isEven(100000);", "true"),
+      ("class Loop { run(n) { if (n == 0) return \"done\"; return this.run(n - 1); } } Loop().run(100000);", "done"),
+      ("fun add(a, b) { return a + b; } fun last(n) { if (n == 0) return add(1, 2); return last(n - 1); } last(10);", "3"),
+    ].iter().cloned().collect();
+
+    for (&input, &expected_result) in test_input.iter() {
+      let val = run(input)?;
+      assert_eq!(val.to_string(), expected_result);
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_properties() -> Result<(), RloxError> {
+    let class = "class Temp { init() { this.c = 0; } f { return this.c * 9 / 5 + 32; } set f(v) { this.c = (v - 32) * 5 / 9; } }";
+    let test_input: HashMap<String, &str> = [
+      (format!("{} Temp().f;", class), "32"),
+      (format!("{} var t = Temp(); t.f = 212; t.c;", class), "100"),
+      (format!("{} var t = Temp(); t.f += 18; t.c;", class), "10"),
+      (format!("{} class Sub < Temp {{}} var t = Sub(); t.f = 50; t.f;", class), "50"),
+    ].iter().cloned().collect();
+
+    for (input, expected_result) in test_input.iter() {
+      assert_eq!(run(input)?.to_string(), *expected_result, "{}", input);
+    }
+
+    assert!(matches!(run("class A { x { return 1; } } A().x = 2;"), Err(RloxError::InterpreterError(_))));
+    assert!(matches!(run("class A { set x() {} }"), Err(RloxError::ParserError(_))));
+    Ok(())
+  }
+
+  #[test]
+  fn test_process_natives() -> Result<(), RloxError> {
+    let interpreter = Interpreter::new();
+    interpreter.set_args(vec!["a".to_string(), "b c".to_string()]);
+    interpreter.set_input(Rc::new(RefCell::new(std::io::Cursor::new("one\r\ntwo\nrest\n"))));
+
+    assert_eq!(run_in(&interpreter, "args();")?.inspect(), "[\"a\", \"b c\"]");
+    assert_eq!(run_in(&interpreter, "readLine();")?.inspect(), "\"one\"");
+    assert_eq!(run_in(&interpreter, "readLine() + readAll();")?.inspect(), "\"tworest\\n\"");
+    assert_eq!(run_in(&interpreter, "readLine();")?, RloxType::NullType);
+    assert_eq!(run_in(&interpreter, "env(\"RLOX_SURELY_UNSET_VARIABLE\");")?, RloxType::NullType);
+
+    assert!(matches!(run("fun f() { exit(3); } f(); print 1;"), Err(RloxError::Exit(3))));
+    assert!(matches!(run("fun f() { exit(1); } assert_throws(f);"), Err(RloxError::Exit(1))));
+    assert!(matches!(run("exit(256);"), Err(RloxError::InterpreterError(_))));
+    Ok(())
+  }
 }
@@ -0,0 +1,495 @@
+// The json namespace.
+//
+// json.parse maps objects onto instances of a plain Object class, arrays
+// onto lists, whole numbers onto integers, or bigints when they don't fit,
+// and other numbers onto floats. json.stringify converts those values
+// back, instance fields are written in alphabetical order.
+use std::{
+  cell::RefCell,
+  collections::HashMap,
+  rc::Rc,
+};
+use num_bigint::BigInt;
+use super::{
+  callable::Callable,
+  interpreter::Interpreter,
+  native_object::NativeObject,
+  rlox_class::RloxClass,
+  rlox_instance::RloxInstance,
+  rlox_type::RloxType,
+  rlox_errors::RloxError,
+  rlox_list::RloxList,
+};
+
+// Deeper documents are rejected instead of overflowing the stack.
+const MAX_DEPTH: usize = 512;
+
+pub fn namespace() -> RloxType {
+  let object_class = RloxClass::new("Object", None, Rc::new(RefCell::new(HashMap::new())));
+
+  RloxType::NativeType(NativeObject::new("json", vec![
+    ("parse", RloxType::CallableType(Box::new(JsonFunction { name: "parse", arity: 1, object_class: object_class.clone() }))),
+    ("stringify", RloxType::CallableType(Box::new(JsonFunction { name: "stringify", arity: 2, object_class }))),
+  ]))
+}
+
+fn error(message: String) -> RloxError {
+  RloxError::InterpreterError(message)
+}
+
+struct JsonParser<'a> {
+  chars: Vec<char>,
+  current: usize,
+  object_class: &'a RloxClass,
+}
+
+impl<'a> JsonParser<'a> {
+  fn parse(text: &str, object_class: &'a RloxClass) -> Result<RloxType, RloxError> {
+    let mut parser = JsonParser { chars: text.chars().collect(), current: 0, object_class };
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.current < parser.chars.len() {
+      return Err(parser.error("unexpected text after the value"));
+    }
+    Ok(value)
+  }
+
+  fn error(&self, message: &str) -> RloxError {
+    let before = &self.chars[..self.current.min(self.chars.len())];
+    let line = before.iter().filter(|&&c| c == '\n').count() + 1;
+    let column = before.iter().rev().take_while(|&&c| c != '\n').count() + 1;
+    error(format!("Invalid JSON at line {}, column {}: {}.", line, column, message))
+  }
+
+  fn peek(&self) -> Option<char> {
+    self.chars.get(self.current).copied()
+  }
+
+  fn skip_whitespace(&mut self) {
+    while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+      self.current += 1;
+    }
+  }
+
+  fn expect(&mut self, expected: char) -> Result<(), RloxError> {
+    self.skip_whitespace();
+    if self.peek() != Some(expected) {
+      return Err(self.error(&format!("expected '{}'", expected)));
+    }
+    self.current += 1;
+    Ok(())
+  }
+
+  // Consumes c when it's next.
+  fn accept(&mut self, c: char) -> bool {
+    self.skip_whitespace();
+    if self.peek() == Some(c) {
+      self.current += 1;
+      return true;
+    }
+    false
+  }
+
+  fn value(&mut self, depth: usize) -> Result<RloxType, RloxError> {
+    if depth > MAX_DEPTH {
+      return Err(self.error("too deeply nested"));
+    }
+
+    self.skip_whitespace();
+    match self.peek() {
+      Some('{') => self.object(depth),
+      Some('[') => self.array(depth),
+      Some('"') => self.string().map(RloxType::StringType),
+      Some('-' | '0'..='9') => self.number(),
+      Some(c) if c.is_ascii_alphabetic() => self.keyword(),
+      Some(_) => Err(self.error("expected a value")),
+      None => Err(self.error("unexpected end of input")),
+    }
+  }
+
+  fn keyword(&mut self) -> Result<RloxType, RloxError> {
+    let start = self.current;
+    while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+      self.current += 1;
+    }
+
+    match self.chars[start..self.current].iter().collect::<String>().as_str() {
+      "true" => Ok(RloxType::BooleanType(true)),
+      "false" => Ok(RloxType::BooleanType(false)),
+      "null" => Ok(RloxType::NullType),
+      _ => {
+        self.current = start;
+        Err(self.error("expected a value"))
+      }
+    }
+  }
+
+  fn object(&mut self, depth: usize) -> Result<RloxType, RloxError> {
+    self.current += 1;
+    let instance = RloxInstance::new(self.object_class.clone());
+
+    if self.accept('}') {
+      return Ok(RloxType::ClassType(instance));
+    }
+
+    loop {
+      self.skip_whitespace();
+      if self.peek() != Some('"') {
+        return Err(self.error("expected a string key"));
+      }
+      let key = self.string()?;
+      self.expect(':')?;
+      let value = self.value(depth + 1)?;
+      instance.set_field(&key, value);
+
+      if !self.accept(',') {
+        self.expect('}')?;
+        return Ok(RloxType::ClassType(instance));
+      }
+    }
+  }
+
+  fn array(&mut self, depth: usize) -> Result<RloxType, RloxError> {
+    self.current += 1;
+    let mut items = vec![];
+
+    if self.accept(']') {
+      return Ok(RloxType::ListType(RloxList::new(items)));
+    }
+
+    loop {
+      items.push(self.value(depth + 1)?);
+      if !self.accept(',') {
+        self.expect(']')?;
+        return Ok(RloxType::ListType(RloxList::new(items)));
+      }
+    }
+  }
+
+  fn string(&mut self) -> Result<String, RloxError> {
+    self.current += 1;
+    let mut text = String::new();
+
+    loop {
+      let c = match self.peek() {
+        Some(c) => c,
+        None => return Err(self.error("unterminated string")),
+      };
+      self.current += 1;
+
+      match c {
+        '"' => return Ok(text),
+        '\\' => text.push(self.escape()?),
+        c if (c as u32) < 0x20 => {
+          self.current -= 1;
+          return Err(self.error("control character in string"));
+        }
+        c => text.push(c),
+      }
+    }
+  }
+
+  fn escape(&mut self) -> Result<char, RloxError> {
+    let c = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+    self.current += 1;
+
+    match c {
+      '"' => Ok('"'),
+      '\\' => Ok('\\'),
+      '/' => Ok('/'),
+      'b' => Ok('\u{8}'),
+      'f' => Ok('\u{c}'),
+      'n' => Ok('\n'),
+      'r' => Ok('\r'),
+      't' => Ok('\t'),
+      'u' => {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+          return char::from_u32(high).ok_or_else(|| self.error("invalid unicode escape"));
+        }
+        // Surrogate pair.
+        if self.peek() != Some('\\') || self.chars.get(self.current + 1) != Some(&'u') {
+          return Err(self.error("invalid unicode escape"));
+        }
+        self.current += 2;
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+          return Err(self.error("invalid unicode escape"));
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).ok_or_else(|| self.error("invalid unicode escape"))
+      }
+      _ => {
+        self.current -= 1;
+        Err(self.error(&format!("invalid escape '\\{}'", c)))
+      }
+    }
+  }
+
+  fn hex4(&mut self) -> Result<u32, RloxError> {
+    let digits: String = self.chars.iter().skip(self.current).take(4).collect();
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+      return Err(self.error("invalid unicode escape"));
+    }
+    self.current += 4;
+    Ok(u32::from_str_radix(&digits, 16).unwrap())
+  }
+
+  // Points the error at the start of the number.
+  fn invalid_number(&mut self, start: usize) -> RloxError {
+    self.current = start;
+    self.error("invalid number")
+  }
+
+  fn number(&mut self) -> Result<RloxType, RloxError> {
+    let start = self.current;
+    let digits = |parser: &mut JsonParser| {
+      let from = parser.current;
+      while parser.peek().is_some_and(|c| c.is_ascii_digit()) {
+        parser.current += 1;
+      }
+      parser.current - from
+    };
+
+    if self.peek() == Some('-') {
+      self.current += 1;
+    }
+    let int_start = self.current;
+    let int_digits = digits(self);
+    if int_digits == 0 || (int_digits > 1 && self.chars[int_start] == '0') {
+      return Err(self.invalid_number(start));
+    }
+
+    let mut whole = true;
+    if self.peek() == Some('.') {
+      self.current += 1;
+      whole = false;
+      if digits(self) == 0 {
+        return Err(self.invalid_number(start));
+      }
+    }
+    if matches!(self.peek(), Some('e' | 'E')) {
+      self.current += 1;
+      whole = false;
+      if matches!(self.peek(), Some('+' | '-')) {
+        self.current += 1;
+      }
+      if digits(self) == 0 {
+        return Err(self.invalid_number(start));
+      }
+    }
+
+    let text: String = self.chars[start..self.current].iter().collect();
+    if whole {
+      if let Ok(i) = text.parse::<i64>() {
+        return Ok(RloxType::IntegerType(i));
+      }
+      if let Ok(b) = text.parse::<BigInt>() {
+        return Ok(RloxType::BigIntType(b));
+      }
+    }
+    match text.parse::<f64>() {
+      Ok(n) if n.is_finite() => Ok(RloxType::NumberType(n)),
+      Ok(_) => {
+        self.current = start;
+        Err(self.error("number out of range"))
+      }
+      Err(_) => Err(self.invalid_number(start)),
+    }
+  }
+}
+
+fn quote(text: &str) -> String {
+  let mut quoted = String::from("\"");
+  for c in text.chars() {
+    match c {
+      '"' => quoted.push_str("\\\""),
+      '\\' => quoted.push_str("\\\\"),
+      '\n' => quoted.push_str("\\n"),
+      '\r' => quoted.push_str("\\r"),
+      '\t' => quoted.push_str("\\t"),
+      c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+      c => quoted.push(c),
+    }
+  }
+  quoted.push('"');
+  quoted
+}
+
+struct JsonWriter {
+  indent: usize,
+  // Lists and instances being written, to detect cycles.
+  visiting: Vec<usize>,
+}
+
+impl JsonWriter {
+  fn write(&mut self, value: &RloxType, depth: usize) -> Result<String, RloxError> {
+    match value {
+      RloxType::NullType => Ok("null".to_string()),
+      RloxType::BooleanType(b) => Ok(b.to_string()),
+      RloxType::IntegerType(i) => Ok(i.to_string()),
+      RloxType::BigIntType(b) => Ok(b.to_string()),
+      RloxType::DecimalType(d) => Ok(d.to_string()),
+      RloxType::NumberType(n) if n.is_finite() => Ok(format!("{:?}", n)),
+      RloxType::NumberType(n) => Err(error(format!("Can't convert {} to JSON.", n))),
+      RloxType::StringType(s) => Ok(quote(s)),
+      RloxType::ListType(list) => {
+        let items = self.nested(list.id(), depth, |writer| {
+          list.items().iter().map(|item| writer.write(item, depth + 1)).collect()
+        })?;
+        Ok(self.join('[', items, ']', depth))
+      }
+      RloxType::ClassType(instance) => {
+        let members = self.nested(instance.id(), depth, |writer| {
+          let separator = if writer.indent > 0 { ": " } else { ":" };
+          instance.fields().iter()
+            .map(|(name, value)| Ok(format!("{}{}{}", quote(name), separator, writer.write(value, depth + 1)?)))
+            .collect()
+        })?;
+        Ok(self.join('{', members, '}', depth))
+      }
+      _ => Err(error(format!("Can't convert {} to JSON.", value.type_name()))),
+    }
+  }
+
+  fn nested(&mut self, id: usize, depth: usize, write: impl FnOnce(&mut JsonWriter) -> Result<Vec<String>, RloxError>) -> Result<Vec<String>, RloxError> {
+    if self.visiting.contains(&id) {
+      return Err(error("Can't convert a cyclic structure to JSON.".to_string()));
+    }
+    if depth > MAX_DEPTH {
+      return Err(error("Can't convert a structure nested this deeply to JSON.".to_string()));
+    }
+
+    self.visiting.push(id);
+    let parts = write(self);
+    self.visiting.pop();
+    parts
+  }
+
+  fn join(&self, open: char, parts: Vec<String>, close: char, depth: usize) -> String {
+    if parts.is_empty() {
+      return format!("{}{}", open, close);
+    }
+    if self.indent == 0 {
+      return format!("{}{}{}", open, parts.join(","), close);
+    }
+
+    let inner = " ".repeat(self.indent * (depth + 1));
+    let outer = " ".repeat(self.indent * depth);
+    let lines: Vec<String> = parts.iter().map(|part| format!("{}{}", inner, part)).collect();
+    format!("{}\n{}\n{}{}", open, lines.join(",\n"), outer, close)
+  }
+}
+
+#[derive(Clone)]
+struct JsonFunction {
+  name: &'static str,
+  arity: usize,
+  object_class: RloxClass,
+}
+
+impl Callable for JsonFunction {
+  fn arity(&self) -> usize {
+    self.arity
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    match self.name {
+      "parse" => match &arguments[0] {
+        RloxType::StringType(text) => JsonParser::parse(text, &self.object_class),
+        value => Err(error(format!("json.parse() expects a string, not {}.", value.type_name()))),
+      },
+      // Indent is the number of spaces per level, nil or 0 for one line.
+      "stringify" => {
+        let indent = match &arguments[1] {
+          RloxType::NullType => 0,
+          RloxType::IntegerType(i) if (0..=10).contains(i) => *i as usize,
+          value => return Err(error(format!("json.stringify() expects an indent between 0 and 10 or nil, got {}.", value.inspect()))),
+        };
+        let mut writer = JsonWriter { indent, visiting: vec![] };
+        writer.write(&arguments[0], 0).map(RloxType::StringType)
+      }
+      _ => Err(error(format!("Undefined property '{}' for json.", self.name))),
+    }
+  }
+
+  fn name(&self) -> String {
+    format!("<native json.{} function>", self.name)
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn class() -> RloxClass {
+    RloxClass::new("Object", None, Rc::new(RefCell::new(HashMap::new())))
+  }
+
+  fn round_trip(text: &str, indent: usize) -> Result<String, RloxError> {
+    let value = JsonParser::parse(text, &class())?;
+    JsonWriter { indent, visiting: vec![] }.write(&value, 0)
+  }
+
+  #[test]
+  fn test_round_trip() -> Result<(), RloxError> {
+    let cases = [
+      ("null", "null"),
+      (" [1, -2.5, 1e3, true, \"a\\u00e9\\ud83d\\ude00\\n\"] ", "[1,-2.5,1000.0,true,\"aé😀\\n\"]"),
+      ("{\"b\": {}, \"a\": []}", "{\"a\":[],\"b\":{}}"),
+      ("123456789012345678901234567890", "123456789012345678901234567890"),
+    ];
+
+    for (input, expected) in cases.iter() {
+      assert_eq!(round_trip(input, 0)?, *expected, "{}", input);
+    }
+
+    assert_eq!(round_trip("{\"a\": [1, {\"b\": null}]}", 2)?, "{\n  \"a\": [\n    1,\n    {\n      \"b\": null\n    }\n  ]\n}");
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_errors() {
+    let cases = [
+      ("", "Invalid JSON at line 1, column 1: unexpected end of input."),
+      ("[1, 2", "Invalid JSON at line 1, column 6: expected ']'."),
+      ("{\"a\" 1}", "Invalid JSON at line 1, column 6: expected ':'."),
+      ("{a: 1}", "Invalid JSON at line 1, column 2: expected a string key."),
+      ("[01]", "Invalid JSON at line 1, column 2: invalid number."),
+      ("[1e400]", "Invalid JSON at line 1, column 2: number out of range."),
+      ("-1.5E+999", "Invalid JSON at line 1, column 1: number out of range."),
+      ("[1,\n tru]", "Invalid JSON at line 2, column 2: expected a value."),
+      ("\"\\x\"", "Invalid JSON at line 1, column 3: invalid escape '\\x'."),
+      ("1 2", "Invalid JSON at line 1, column 3: unexpected text after the value."),
+    ];
+
+    for (input, expected) in cases.iter() {
+      match JsonParser::parse(input, &class()) {
+        Err(e) => assert_eq!(e.to_string(), *expected, "{}", input),
+        Ok(value) => panic!("{} parsed as {}", input, value),
+      }
+    }
+
+    let deep = "[".repeat(MAX_DEPTH + 2);
+    assert!(JsonParser::parse(&deep, &class()).is_err());
+  }
+
+  #[test]
+  fn test_stringify_errors() {
+    let instance = RloxInstance::new(class());
+    let cyclic = RloxType::ListType(RloxList::new(vec![RloxType::ClassType(instance.clone())]));
+    instance.set_field("items", cyclic.clone());
+
+    let mut writer = JsonWriter { indent: 0, visiting: vec![] };
+    assert_eq!(writer.write(&cyclic, 0).unwrap_err().to_string(), "Can't convert a cyclic structure to JSON.");
+    assert_eq!(writer.write(&RloxType::NumberType(f64::NAN), 0).unwrap_err().to_string(), "Can't convert NaN to JSON.");
+
+    // The same list twice isn't a cycle.
+    let shared = RloxType::ListType(RloxList::new(vec![]));
+    let twice = RloxType::ListType(RloxList::new(vec![shared.clone(), shared]));
+    assert_eq!(writer.write(&twice, 0).unwrap(), "[[],[]]");
+  }
+}
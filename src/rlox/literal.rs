@@ -1,27 +1,110 @@
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter, Result};
+use num_bigint::BigInt;
+use rust_decimal::Decimal;
 use super::{
+  numeric,
   callable::Callable,
+  rlox_class::RloxClass,
   rlox_instance::RloxInstance,
+  rlox_list::RloxList,
+  native_object::NativeObject,
+  gc::{GcRef, Trace},
 };
 
 #[derive(Clone, Debug)]
 pub enum Literal {
   StringType(String),
+  IntegerType(i64),
   NumberType(f64),
+  BigIntType(BigInt),
+  DecimalType(Decimal),
   BooleanType(bool),
   CallableType(Box<dyn Callable>),
   ClassType(RloxInstance),
+  ListType(RloxList),
+  NativeType(NativeObject),
   NullType,
 }
 
+impl Literal {
+  pub fn type_name(&self) -> String {
+    match self {
+      Literal::StringType(_) => "string".to_string(),
+      Literal::IntegerType(_) => "integer".to_string(),
+      Literal::NumberType(_) => "number".to_string(),
+      Literal::BigIntType(_) => "bigint".to_string(),
+      Literal::DecimalType(_) => "decimal".to_string(),
+      Literal::BooleanType(_) => "boolean".to_string(),
+      Literal::CallableType(c) => match c.as_any().downcast_ref::<RloxClass>() {
+        Some(_) => "class".to_string(),
+        None => "function".to_string(),
+      },
+      Literal::ClassType(k) => k.as_string(),
+      Literal::ListType(_) => "list".to_string(),
+      Literal::NativeType(n) => n.name(),
+      Literal::NullType => "nil".to_string(),
+    }
+  }
+
+  // Representation of the value used by the REPL and the inspect native,
+  // unlike Display it quotes strings and shows fields of instances.
+  pub fn inspect(&self) -> String {
+    self.inspect_with(&mut Vec::new())
+  }
+
+  fn inspect_with(&self, visited: &mut Vec<usize>) -> String {
+    match self {
+      Literal::StringType(s) => format!("\"{}\"", s.escape_debug()),
+      Literal::BigIntType(b) => format!("{}n", b),
+      Literal::DecimalType(d) => format!("{}m", d),
+      Literal::CallableType(c) => c.inspect(),
+      Literal::ClassType(k) => {
+        if visited.contains(&k.id()) {
+          return format!("{} {{ ... }}", k.class_name());
+        }
+
+        visited.push(k.id());
+        let fields: Vec<String> = k
+          .fields()
+          .iter()
+          .map(|(name, value)| format!("{}: {}", name, value.inspect_with(visited)))
+          .collect();
+        visited.pop();
+
+        if fields.is_empty() {
+          format!("{} {{}}", k.class_name())
+        } else {
+          format!("{} {{ {} }}", k.class_name(), fields.join(", "))
+        }
+      }
+      Literal::ListType(l) => {
+        if visited.contains(&l.id()) {
+          return "[...]".to_string();
+        }
+
+        visited.push(l.id());
+        let items: Vec<String> = l.items().iter().map(|item| item.inspect_with(visited)).collect();
+        visited.pop();
+        format!("[{}]", items.join(", "))
+      }
+      Literal::NullType => "nil".to_string(),
+      _ => self.to_string(),
+    }
+  }
+}
+
 impl PartialEq for Literal {
   fn eq(&self, other: &Self) -> bool {
       match (self, other) {
           (Literal::StringType(sl), Literal::StringType(sr)) => sl == sr,
           (Literal::NumberType(nl), Literal::NumberType(nr)) => nl == nr,
+          (l, r) if numeric::is_number(l) && numeric::is_number(r) => numeric::compare(l, r) == Some(Ordering::Equal),
           (Literal::BooleanType(bl), Literal::BooleanType(br)) => bl == br,
           (Literal::CallableType(cl), Literal::CallableType(cr)) => cl == cr,
           (Literal::ClassType(kl), Literal::ClassType(kr)) => kl == kr,
+          (Literal::ListType(ll), Literal::ListType(lr)) => ll == lr,
+          (Literal::NativeType(nl), Literal::NativeType(nr)) => nl == nr,
           (Literal::NullType, Literal::NullType) => true,
           (_, _) => false,
       }
@@ -32,11 +115,28 @@ impl Display for Literal {
   fn fmt(&self, f: &mut Formatter) -> Result {
     match self {
       Literal::StringType(s) => write!(f, "{}", s),
+      Literal::IntegerType(i) => write!(f, "{}", i),
       Literal::NumberType(n) => write!(f, "{}", n),
+      Literal::BigIntType(b) => write!(f, "{}", b),
+      Literal::DecimalType(d) => write!(f, "{}", d),
       Literal::BooleanType(b) => write!(f, "{}", b),
       Literal::CallableType(c) => write!(f, "{}", c.name()),
       Literal::ClassType(k) => write!(f, "{}", k.as_string()),
+      Literal::ListType(_) => write!(f, "{}", self.inspect()),
+      Literal::NativeType(n) => write!(f, "{}", n),
       Literal::NullType => write!(f, "null"),
     }
   }
 }
+
+impl Trace for Literal {
+  fn trace(&self, refs: &mut Vec<GcRef>) {
+    match self {
+      Literal::CallableType(c) => c.trace(refs),
+      Literal::ClassType(instance) => instance.trace(refs),
+      Literal::ListType(list) => list.trace(refs),
+      Literal::NativeType(native) => native.trace(refs),
+      _ => (),
+    }
+  }
+}
@@ -0,0 +1,303 @@
+// The math namespace.
+//
+// Functions take any kind of number. abs, min and max keep the type of
+// their arguments, floor, ceil and round return integers when the result
+// fits one, everything else works on floats.
+//
+// math.Random(seed) creates a generator, the same seed always produces the
+// same numbers on every platform.
+use std::{
+  cell::Cell,
+  cmp::Ordering,
+  rc::Rc,
+};
+use num_traits::Signed;
+use super::{
+  callable::Callable,
+  interpreter::Interpreter,
+  native_object::NativeObject,
+  rlox_type::RloxType,
+  rlox_errors::RloxError,
+  token_type::TokenType,
+  numeric,
+};
+
+const FUNCTIONS: &[(&str, usize)] = &[
+  ("sqrt", 1),
+  ("pow", 2),
+  ("abs", 1),
+  ("floor", 1),
+  ("ceil", 1),
+  ("round", 1),
+  ("min", 2),
+  ("max", 2),
+  ("sin", 1),
+  ("cos", 1),
+  ("tan", 1),
+  ("asin", 1),
+  ("acos", 1),
+  ("atan", 1),
+  ("atan2", 2),
+  ("exp", 1),
+  ("log", 1),
+  ("log2", 1),
+  ("log10", 1),
+];
+
+pub fn namespace() -> RloxType {
+  let mut members: Vec<(&str, RloxType)> = FUNCTIONS.iter()
+    .map(|(name, arity)| (*name, RloxType::CallableType(Box::new(MathFunction { name, arity: *arity }))))
+    .collect();
+
+  members.push(("PI", RloxType::NumberType(std::f64::consts::PI)));
+  members.push(("E", RloxType::NumberType(std::f64::consts::E)));
+  members.push(("INF", RloxType::NumberType(f64::INFINITY)));
+  members.push(("NAN", RloxType::NumberType(f64::NAN)));
+  members.push(("Random", RloxType::CallableType(Box::new(NewRandom {}))));
+
+  RloxType::NativeType(NativeObject::new("math", members))
+}
+
+fn float_argument(name: &str, value: &RloxType) -> Result<f64, RloxError> {
+  match numeric::to_float(value) {
+    Some(n) => Ok(n),
+    None => Err(RloxError::InterpreterError(format!("math.{}() expects a number, not {}.", name, value.type_name()))),
+  }
+}
+
+fn integer_argument(name: &str, value: &RloxType) -> Result<i64, RloxError> {
+  match value {
+    RloxType::IntegerType(i) => Ok(*i),
+    _ => Err(RloxError::InterpreterError(format!("{}() expects an integer, not {}.", name, value.type_name()))),
+  }
+}
+
+// Integer when the float is a whole number in range.
+fn whole(n: f64) -> RloxType {
+  if n.is_finite() && n >= i64::MIN as f64 && n < i64::MAX as f64 {
+    RloxType::IntegerType(n as i64)
+  } else {
+    RloxType::NumberType(n)
+  }
+}
+
+#[derive(Clone)]
+struct MathFunction {
+  name: &'static str,
+  arity: usize,
+}
+
+impl MathFunction {
+  fn round(&self, value: &RloxType, round: fn(f64) -> f64) -> Result<RloxType, RloxError> {
+    match value {
+      RloxType::IntegerType(_) | RloxType::BigIntType(_) => Ok(value.clone()),
+      RloxType::DecimalType(d) => {
+        let rounded = match self.name {
+          "floor" => d.floor(),
+          "ceil" => d.ceil(),
+          _ => d.round_dp_with_strategy(0, rust_decimal::RoundingStrategy::MidpointAwayFromZero),
+        };
+        Ok(RloxType::DecimalType(rounded))
+      }
+      _ => Ok(whole(round(float_argument(self.name, value)?))),
+    }
+  }
+
+  fn pick(&self, left: &RloxType, right: &RloxType, keep: Ordering) -> Result<RloxType, RloxError> {
+    match numeric::compare(left, right) {
+      Some(ordering) if ordering == keep || ordering == Ordering::Equal => Ok(left.clone()),
+      Some(_) => Ok(right.clone()),
+      None if numeric::is_number(left) && numeric::is_number(right) => Ok(RloxType::NumberType(f64::NAN)),
+      None => Err(RloxError::InterpreterError(format!("math.{}() expects numbers, not {} and {}.", self.name, left.type_name(), right.type_name()))),
+    }
+  }
+}
+
+impl Callable for MathFunction {
+  fn arity(&self) -> usize {
+    self.arity
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let float = |i: usize| float_argument(self.name, &arguments[i]);
+    let number = |n: f64| Ok(RloxType::NumberType(n));
+
+    match self.name {
+      "abs" => match &arguments[0] {
+        RloxType::IntegerType(i) => i.checked_abs().map(RloxType::IntegerType).ok_or_else(|| RloxError::InterpreterError("Integer overflow.".to_string())),
+        RloxType::BigIntType(b) => Ok(RloxType::BigIntType(b.abs())),
+        RloxType::DecimalType(d) => Ok(RloxType::DecimalType(d.abs())),
+        _ => number(float(0)?.abs()),
+      },
+      "pow" => numeric::binary(&TokenType::STARSTAR, &arguments[0], &arguments[1]),
+      "floor" => self.round(&arguments[0], f64::floor),
+      "ceil" => self.round(&arguments[0], f64::ceil),
+      "round" => self.round(&arguments[0], f64::round),
+      "min" => self.pick(&arguments[0], &arguments[1], Ordering::Less),
+      "max" => self.pick(&arguments[0], &arguments[1], Ordering::Greater),
+      "sqrt" => number(float(0)?.sqrt()),
+      "sin" => number(float(0)?.sin()),
+      "cos" => number(float(0)?.cos()),
+      "tan" => number(float(0)?.tan()),
+      "asin" => number(float(0)?.asin()),
+      "acos" => number(float(0)?.acos()),
+      "atan" => number(float(0)?.atan()),
+      "atan2" => number(float(0)?.atan2(float(1)?)),
+      "exp" => number(float(0)?.exp()),
+      "log" => number(float(0)?.ln()),
+      "log2" => number(float(0)?.log2()),
+      "log10" => number(float(0)?.log10()),
+      _ => Err(RloxError::InterpreterError(format!("Undefined property '{}' for math.", self.name))),
+    }
+  }
+
+  fn name(&self) -> String {
+    format!("<native math.{} function>", self.name)
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+struct NewRandom {}
+
+impl Callable for NewRandom {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let state = Rc::new(Cell::new(integer_argument("math.Random", &arguments[0])? as u64));
+    let method = |name: &'static str, arity: usize| {
+      (name, RloxType::CallableType(Box::new(RandomMethod { state: state.clone(), name, arity })))
+    };
+
+    Ok(RloxType::NativeType(NativeObject::new("Random", vec![
+      method("next", 0),
+      method("int", 2),
+      method("choice", 1),
+    ])))
+  }
+
+  fn name(&self) -> String {
+    String::from("<native math.Random function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+struct RandomMethod {
+  state: Rc<Cell<u64>>,
+  name: &'static str,
+  arity: usize,
+}
+
+impl RandomMethod {
+  // SplitMix64, small and good enough for simulations, not for secrets.
+  fn next_u64(&self) -> u64 {
+    let state = self.state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+    self.state.set(state);
+
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+  }
+
+  // Uniform in [0, bound).
+  fn below(&self, bound: u128) -> u128 {
+    (self.next_u64() as u128 * bound) >> 64
+  }
+}
+
+impl Callable for RandomMethod {
+  fn arity(&self) -> usize {
+    self.arity
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    match self.name {
+      // Float in [0, 1).
+      "next" => Ok(RloxType::NumberType((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64)),
+      // Integer between min and max, both included.
+      "int" => {
+        let min = integer_argument("int", &arguments[0])?;
+        let max = integer_argument("int", &arguments[1])?;
+        if min > max {
+          return Err(RloxError::InterpreterError(format!("int() expects min <= max, got {} and {}.", min, max)));
+        }
+        let offset = self.below((max as i128 - min as i128 + 1) as u128);
+        Ok(RloxType::IntegerType((min as i128 + offset as i128) as i64))
+      }
+      "choice" => match &arguments[0] {
+        RloxType::ListType(list) => {
+          let items = list.items();
+          if items.is_empty() {
+            return Err(RloxError::InterpreterError("choice() expects a non-empty list.".to_string()));
+          }
+          Ok(items[self.below(items.len() as u128) as usize].clone())
+        }
+        value => Err(RloxError::InterpreterError(format!("choice() expects a list, not {}.", value.type_name()))),
+      },
+      _ => Err(RloxError::InterpreterError(format!("Undefined property '{}' for Random.", self.name))),
+    }
+  }
+
+  fn name(&self) -> String {
+    format!("<native Random.{} method>", self.name)
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn random(seed: i64) -> RandomMethod {
+    RandomMethod { state: Rc::new(Cell::new(seed as u64)), name: "int", arity: 2 }
+  }
+
+  #[test]
+  fn test_seeded_random_is_reproducible() {
+    let sequence = |seed| {
+      let r = random(seed);
+      (0..5).map(|_| r.next_u64()).collect::<Vec<u64>>()
+    };
+
+    assert_eq!(sequence(42), sequence(42));
+    assert_ne!(sequence(42), sequence(43));
+    // SplitMix64 reference output for seed 0.
+    assert_eq!(random(0).next_u64(), 0xE220_A839_7B1D_CDAF);
+  }
+
+  #[test]
+  fn test_random_int_range() -> Result<(), RloxError> {
+    let r = random(7);
+    let interpreter = Interpreter::new();
+    for _ in 0..1000 {
+      match r.call(&interpreter, vec![RloxType::IntegerType(-2), RloxType::IntegerType(2)])? {
+        RloxType::IntegerType(i) => assert!((-2..=2).contains(&i)),
+        value => panic!("unexpected {}", value),
+      }
+    }
+
+    let full = r.call(&interpreter, vec![RloxType::IntegerType(i64::MIN), RloxType::IntegerType(i64::MAX)]);
+    assert!(full.is_ok());
+    Ok(())
+  }
+
+  #[test]
+  fn test_rounding() {
+    assert_eq!(whole(2.0), RloxType::IntegerType(2));
+    assert!(matches!(whole(f64::INFINITY), RloxType::NumberType(n) if n.is_infinite()));
+    assert!(matches!(whole(1e300), RloxType::NumberType(_)));
+  }
+}
@@ -18,6 +18,17 @@ mod rlox_errors;
 mod resolver;
 mod rlox_class;
 mod rlox_instance;
+mod gc;
+mod numeric;
+mod rlox_list;
+mod native_object;
+mod math;
+mod time;
+mod fs;
+mod json;
+mod regex;
+mod format;
+mod string_methods;
 
 pub use self::ast_printer::*;
 pub use self::error_handler::*;
@@ -31,3 +42,5 @@ pub use self::token_type::*;
 pub use self::stmt::*;
 pub use self::rlox_errors::RloxError;
 pub use self::resolver::*;
+pub use self::gc::{collect_garbage, live_objects};
+pub use self::fs::{FsAccess, FsPolicy};
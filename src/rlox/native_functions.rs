@@ -3,6 +3,10 @@ use super::{
   interpreter::Interpreter,
   rlox_type::RloxType,
   rlox_errors::RloxError,
+  rlox_list::RloxList,
+  gc,
+  numeric,
+  format,
 };
 use chrono;
 
@@ -21,7 +25,8 @@ impl Callable for Clock {
   }
 
   fn call(&self, _interpreter: &Interpreter, _arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
-    Ok(RloxType::NumberType(chrono::offset::Utc::now().timestamp() as f64))
+    let now = chrono::offset::Utc::now();
+    Ok(RloxType::NumberType(now.timestamp() as f64 + now.timestamp_subsec_nanos() as f64 / 1e9))
   }
 
   fn name(&self) -> String {
@@ -32,3 +37,467 @@ impl Callable for Clock {
     self
   }
 }
+
+fn is_truthy(value: &RloxType) -> bool {
+  !matches!(value, RloxType::NullType | RloxType::BooleanType(false))
+}
+
+// Line by line diff of expected and actual values, lines missing from
+// actual are prefixed with "-" and unexpected lines with "+".
+fn diff(expected: &str, actual: &str) -> String {
+  let expected_lines: Vec<&str> = expected.lines().collect();
+  let actual_lines: Vec<&str> = actual.lines().collect();
+  let mut text = String::new();
+
+  for i in 0..expected_lines.len().max(actual_lines.len()) {
+    match (expected_lines.get(i), actual_lines.get(i)) {
+      (Some(e), Some(a)) if e == a => text.push_str(&format!("\n  {}", e)),
+      (e, a) => {
+        if let Some(e) = e {
+          text.push_str(&format!("\n- {}", e));
+        }
+        if let Some(a) = a {
+          text.push_str(&format!("\n+ {}", a));
+        }
+      }
+    }
+  }
+
+  text
+}
+
+#[derive(Clone)]
+pub struct Assert {}
+
+impl Assert {
+  pub fn new() -> Assert {
+    Assert {}
+  }
+}
+
+impl Callable for Assert {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    if is_truthy(&arguments[0]) {
+      return Ok(RloxType::NullType);
+    }
+
+    Err(RloxError::InterpreterError(format!("assert failed: {} is not truthy.", arguments[0].inspect())))
+  }
+
+  fn name(&self) -> String {
+    String::from("<native assert function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct AssertEq {}
+
+impl AssertEq {
+  pub fn new() -> AssertEq {
+    AssertEq {}
+  }
+}
+
+impl Callable for AssertEq {
+  fn arity(&self) -> usize {
+    2
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let actual = &arguments[0];
+    let expected = &arguments[1];
+    if actual == expected {
+      return Ok(RloxType::NullType);
+    }
+
+    Err(RloxError::InterpreterError(format!(
+      "assert_eq failed (- expected, + actual):{}",
+      diff(&expected.inspect(), &actual.inspect()),
+    )))
+  }
+
+  fn name(&self) -> String {
+    String::from("<native assert_eq function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct AssertThrows {}
+
+impl AssertThrows {
+  pub fn new() -> AssertThrows {
+    AssertThrows {}
+  }
+}
+
+impl Callable for AssertThrows {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  // Calls the given function without arguments and returns the message
+  // of the error it raised.
+  fn call(&self, interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let function = match &arguments[0] {
+      RloxType::CallableType(c) if c.arity() == 0 => c,
+      _ => return Err(RloxError::InterpreterError("assert_throws expects a function without parameters.".to_string())),
+    };
+
+    match function.call(interpreter, Vec::new()) {
+      Ok(_) => Err(RloxError::InterpreterError(format!("assert_throws failed: {} did not throw.", function.name()))),
+      Err(e @ RloxError::Exit(_)) => Err(e),
+      Err(e) => Ok(RloxType::StringType(e.to_string())),
+    }
+  }
+
+  fn name(&self) -> String {
+    String::from("<native assert_throws function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct Inspect {}
+
+impl Inspect {
+  pub fn new() -> Inspect {
+    Inspect {}
+  }
+}
+
+impl Callable for Inspect {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    Ok(RloxType::StringType(arguments[0].inspect()))
+  }
+
+  fn name(&self) -> String {
+    String::from("<native inspect function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct Gc {}
+
+impl Gc {
+  pub fn new() -> Gc {
+    Gc {}
+  }
+}
+
+impl Callable for Gc {
+  fn arity(&self) -> usize {
+    0
+  }
+
+  fn call(&self, _interpreter: &Interpreter, _arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    Ok(RloxType::IntegerType(gc::collect_garbage() as i64))
+  }
+
+  fn name(&self) -> String {
+    String::from("<native gc function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct ToNumber {}
+
+impl ToNumber {
+  pub fn new() -> ToNumber {
+    ToNumber {}
+  }
+}
+
+impl Callable for ToNumber {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    numeric::to_number(&arguments[0])
+  }
+
+  fn name(&self) -> String {
+    String::from("<native number function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct ToBigInt {}
+
+impl ToBigInt {
+  pub fn new() -> ToBigInt {
+    ToBigInt {}
+  }
+}
+
+impl Callable for ToBigInt {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    numeric::to_bigint(&arguments[0])
+  }
+
+  fn name(&self) -> String {
+    String::from("<native bigint function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct ToDecimal {}
+
+impl ToDecimal {
+  pub fn new() -> ToDecimal {
+    ToDecimal {}
+  }
+}
+
+impl Callable for ToDecimal {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    numeric::to_decimal(&arguments[0])
+  }
+
+  fn name(&self) -> String {
+    String::from("<native decimal function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct Args {}
+
+impl Args {
+  pub fn new() -> Args {
+    Args {}
+  }
+}
+
+impl Callable for Args {
+  fn arity(&self) -> usize {
+    0
+  }
+
+  fn call(&self, interpreter: &Interpreter, _arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let args = interpreter.args().into_iter().map(RloxType::StringType).collect();
+    Ok(RloxType::ListType(RloxList::new(args)))
+  }
+
+  fn name(&self) -> String {
+    String::from("<native args function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct ReadLine {}
+
+impl ReadLine {
+  pub fn new() -> ReadLine {
+    ReadLine {}
+  }
+}
+
+impl Callable for ReadLine {
+  fn arity(&self) -> usize {
+    0
+  }
+
+  // The next line of input without its line break, nil at the end.
+  fn call(&self, interpreter: &Interpreter, _arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let mut line = String::new();
+    if interpreter.input().borrow_mut().read_line(&mut line).map_err(input_error)? == 0 {
+      return Ok(RloxType::NullType);
+    }
+
+    if line.ends_with('\n') {
+      line.pop();
+      if line.ends_with('\r') {
+        line.pop();
+      }
+    }
+    Ok(RloxType::StringType(line))
+  }
+
+  fn name(&self) -> String {
+    String::from("<native readLine function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct ReadAll {}
+
+impl ReadAll {
+  pub fn new() -> ReadAll {
+    ReadAll {}
+  }
+}
+
+impl Callable for ReadAll {
+  fn arity(&self) -> usize {
+    0
+  }
+
+  fn call(&self, interpreter: &Interpreter, _arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let mut text = String::new();
+    interpreter.input().borrow_mut().read_to_string(&mut text).map_err(input_error)?;
+    Ok(RloxType::StringType(text))
+  }
+
+  fn name(&self) -> String {
+    String::from("<native readAll function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+fn input_error(e: std::io::Error) -> RloxError {
+  RloxError::InterpreterError(format!("Can't read input: {}.", e))
+}
+
+#[derive(Clone)]
+pub struct Env {}
+
+impl Env {
+  pub fn new() -> Env {
+    Env {}
+  }
+}
+
+impl Callable for Env {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  // Value of the environment variable, nil when it isn't set.
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    match &arguments[0] {
+      RloxType::StringType(name) => Ok(std::env::var(name).map_or(RloxType::NullType, RloxType::StringType)),
+      value => Err(RloxError::InterpreterError(format!("env() expects a string, not {}.", value.type_name()))),
+    }
+  }
+
+  fn name(&self) -> String {
+    String::from("<native env function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct Exit {}
+
+impl Exit {
+  pub fn new() -> Exit {
+    Exit {}
+  }
+}
+
+impl Callable for Exit {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  // Unwinds the script, the runner exits with the code.
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    match &arguments[0] {
+      RloxType::IntegerType(code) if (0..=255).contains(code) => Err(RloxError::Exit(*code as i32)),
+      value => Err(RloxError::InterpreterError(format!("exit() expects an integer between 0 and 255, got {}.", value.inspect()))),
+    }
+  }
+
+  fn name(&self) -> String {
+    String::from("<native exit function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+pub struct Format {}
+
+impl Format {
+  pub fn new() -> Format {
+    Format {}
+  }
+}
+
+impl Callable for Format {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  fn variadic(&self) -> bool {
+    true
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    match &arguments[0] {
+      RloxType::StringType(template) => format::format(template, &arguments[1..]).map(RloxType::StringType),
+      value => Err(RloxError::InterpreterError(format!("format() expects a format string, not {}.", value.type_name()))),
+    }
+  }
+
+  fn name(&self) -> String {
+    String::from("<native format function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
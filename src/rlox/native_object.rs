@@ -0,0 +1,83 @@
+use std::{
+  any::Any,
+  collections::HashMap,
+  fmt,
+  rc::Rc,
+};
+use super::{
+  rlox_type::RloxType,
+  rlox_errors::RloxError,
+  token::Token,
+  gc::{GcRef, Trace},
+};
+
+// Read-only object implemented in Rust, used for the namespaces of the
+// standard library like math and for the objects they create. Objects
+// wrapping a Rust value, like dates, print and compare by its text.
+#[derive(Clone)]
+pub struct NativeObject {
+  name: String,
+  members: Rc<HashMap<String, RloxType>>,
+  value: Option<(Rc<dyn Any>, String)>,
+}
+
+impl NativeObject {
+  pub fn new(name: &str, members: Vec<(&str, RloxType)>) -> NativeObject {
+    NativeObject {
+      name: name.to_string(),
+      members: Rc::new(members.into_iter().map(|(k, v)| (k.to_string(), v)).collect()),
+      value: None,
+    }
+  }
+
+  pub fn with_value<T: Any + fmt::Display>(mut self, value: T) -> NativeObject {
+    let text = value.to_string();
+    self.value = Some((Rc::new(value), text));
+    self
+  }
+
+  pub fn value<T: Any>(&self) -> Option<&T> {
+    self.value.as_ref().and_then(|(value, _)| value.downcast_ref::<T>())
+  }
+
+  pub fn name(&self) -> String {
+    self.name.clone()
+  }
+
+  pub fn get(&self, name: &Token) -> Result<RloxType, RloxError> {
+    match self.members.get(&name.lexeme) {
+      Some(value) => Ok(value.clone()),
+      None => Err(RloxError::InterpreterError(format!("Undefined property '{}' for {}.", name.lexeme, self.name))),
+    }
+  }
+}
+
+impl PartialEq for NativeObject {
+  fn eq(&self, other: &Self) -> bool {
+    match (&self.value, &other.value) {
+      (Some((_, left)), Some((_, right))) => self.name == other.name && left == right,
+      _ => Rc::ptr_eq(&self.members, &other.members),
+    }
+  }
+}
+
+impl fmt::Display for NativeObject {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match &self.value {
+      Some((_, text)) => write!(f, "{}", text),
+      None => write!(f, "<native {}>", self.name),
+    }
+  }
+}
+
+impl fmt::Debug for NativeObject {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "NativeObject({})", self)
+  }
+}
+
+impl Trace for NativeObject {
+  fn trace(&self, refs: &mut Vec<GcRef>) {
+    self.members.values().for_each(|value| value.trace(refs));
+  }
+}
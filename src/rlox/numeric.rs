@@ -0,0 +1,529 @@
+// Arithmetic and comparison of numbers.
+//
+// Integers stay integers as long as both operands are integers, overflow
+// is an error instead of wrapping and division truncates toward zero.
+// When either operand is a float the other one is promoted to float.
+//
+// Bigints and decimals are exact, integers are promoted to them and a
+// bigint meeting a decimal becomes a decimal. They are never silently
+// mixed with floats, scripts convert explicitly with number(), bigint()
+// and decimal().
+//
+// % takes the sign of the dividend, like /, and div floors its result.
+// Bitwise operators only work on integers and bigints.
+use std::cmp::Ordering;
+use std::str::FromStr;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+use rust_decimal::Decimal;
+use super::{
+  rlox_type::RloxType,
+  rlox_errors::RloxError,
+  token_type::TokenType,
+};
+
+// Both operands converted to a common type.
+enum Operands {
+  Integers(i64, i64),
+  BigInts(BigInt, BigInt),
+  Decimals(Decimal, Decimal),
+  Floats(f64, f64),
+}
+
+pub fn is_number(value: &RloxType) -> bool {
+  matches!(value, RloxType::IntegerType(_) | RloxType::NumberType(_) | RloxType::BigIntType(_) | RloxType::DecimalType(_))
+}
+
+pub fn binary(token_type: &TokenType, left: &RloxType, right: &RloxType) -> Result<RloxType, RloxError> {
+  if let Some(ordering) = comparison(token_type) {
+    promote(token_type, left, right)?;
+    return Ok(RloxType::BooleanType(compare(left, right).is_some_and(|o| ordering.contains(&o))));
+  }
+
+  match promote(token_type, left, right)? {
+    Operands::Integers(l, r) => integer_binary(token_type, l, r),
+    Operands::BigInts(l, r) => bigint_binary(token_type, l, r),
+    Operands::Decimals(l, r) => decimal_binary(token_type, l, r),
+    Operands::Floats(l, r) => float_binary(token_type, l, r),
+  }
+}
+
+pub fn negate(value: &RloxType) -> Result<RloxType, RloxError> {
+  match value {
+    RloxType::IntegerType(i) => i.checked_neg().map(RloxType::IntegerType).ok_or_else(overflow),
+    RloxType::NumberType(n) => Ok(RloxType::NumberType(-n)),
+    RloxType::BigIntType(b) => Ok(RloxType::BigIntType(-b)),
+    RloxType::DecimalType(d) => Ok(RloxType::DecimalType(-d)),
+    _ => Err(RloxError::InterpreterError("Invalid type".to_string())),
+  }
+}
+
+pub fn invert(value: &RloxType) -> Result<RloxType, RloxError> {
+  match value {
+    RloxType::IntegerType(i) => Ok(RloxType::IntegerType(!i)),
+    RloxType::BigIntType(b) => Ok(RloxType::BigIntType(!b)),
+    _ => Err(RloxError::InterpreterError(format!("unsupported operand type for TILDE: operand type must be integer, not {}", value.type_name()))),
+  }
+}
+
+// Numbers of different types are equal when they have the same value.
+pub fn compare(left: &RloxType, right: &RloxType) -> Option<Ordering> {
+  match (left, right) {
+    (RloxType::IntegerType(l), RloxType::NumberType(r)) => compare_integer_to_float(*l, *r),
+    (RloxType::NumberType(l), RloxType::IntegerType(r)) => compare_integer_to_float(*r, *l).map(Ordering::reverse),
+    _ => match promote(&TokenType::EQUALEQUAL, left, right).ok()? {
+      Operands::Integers(l, r) => Some(l.cmp(&r)),
+      Operands::BigInts(l, r) => Some(l.cmp(&r)),
+      Operands::Decimals(l, r) => Some(l.cmp(&r)),
+      Operands::Floats(l, r) => l.partial_cmp(&r),
+    },
+  }
+}
+
+pub fn to_float(value: &RloxType) -> Option<f64> {
+  match value {
+    RloxType::IntegerType(i) => Some(*i as f64),
+    RloxType::NumberType(n) => Some(*n),
+    RloxType::BigIntType(b) => b.to_f64(),
+    RloxType::DecimalType(d) => d.to_f64(),
+    _ => None,
+  }
+}
+
+// Conversions used by the number(), bigint() and decimal() natives.
+pub fn to_number(value: &RloxType) -> Result<RloxType, RloxError> {
+  let number = match value {
+    RloxType::StringType(s) => s.trim().parse::<f64>().ok(),
+    _ => to_float(value),
+  };
+
+  number.map(RloxType::NumberType).ok_or_else(|| conversion_error(value, "number"))
+}
+
+pub fn to_bigint(value: &RloxType) -> Result<RloxType, RloxError> {
+  bigint_of(value).map(RloxType::BigIntType).ok_or_else(|| conversion_error(value, "bigint"))
+}
+
+pub fn to_decimal(value: &RloxType) -> Result<RloxType, RloxError> {
+  decimal_of(value).map(RloxType::DecimalType).ok_or_else(|| conversion_error(value, "decimal"))
+}
+
+fn bigint_of(value: &RloxType) -> Option<BigInt> {
+  match value {
+    RloxType::IntegerType(i) => Some(BigInt::from(*i)),
+    RloxType::BigIntType(b) => Some(b.clone()),
+    RloxType::DecimalType(d) if d.fract().is_zero() => BigInt::from_str(&d.trunc().to_string()).ok(),
+    RloxType::NumberType(n) if n.fract() == 0.0 => BigInt::from_f64(*n),
+    RloxType::StringType(s) => BigInt::from_str(s.trim()).ok(),
+    _ => None,
+  }
+}
+
+fn decimal_of(value: &RloxType) -> Option<Decimal> {
+  match value {
+    RloxType::IntegerType(i) => Some(Decimal::from(*i)),
+    RloxType::BigIntType(b) => Decimal::from_str(&b.to_string()).ok(),
+    RloxType::DecimalType(d) => Some(*d),
+    // Shortest representation of the float, so 0.1 becomes 0.1 and not
+    // the binary approximation of it.
+    RloxType::NumberType(n) if n.is_finite() => Decimal::from_str(&n.to_string()).ok(),
+    RloxType::StringType(s) => Decimal::from_str(s.trim()).ok(),
+    _ => None,
+  }
+}
+
+fn conversion_error(value: &RloxType, type_name: &str) -> RloxError {
+  RloxError::InterpreterError(format!("Can't convert {} to {}.", value.inspect(), type_name))
+}
+
+fn promote(token_type: &TokenType, left: &RloxType, right: &RloxType) -> Result<Operands, RloxError> {
+  let operands = match (left, right) {
+    (RloxType::IntegerType(l), RloxType::IntegerType(r)) => Operands::Integers(*l, *r),
+    (RloxType::NumberType(_), RloxType::NumberType(_) | RloxType::IntegerType(_))
+    | (RloxType::IntegerType(_), RloxType::NumberType(_)) => {
+      Operands::Floats(to_float(left).unwrap_or(f64::NAN), to_float(right).unwrap_or(f64::NAN))
+    }
+    (RloxType::NumberType(_), _) | (_, RloxType::NumberType(_)) => {
+      return Err(RloxError::InterpreterError(format!("unsupported operand type(s) for {}: can't mix {} and {}, convert with number() or decimal()", token_type.name(), left.type_name(), right.type_name())));
+    }
+    (RloxType::DecimalType(_), _) | (_, RloxType::DecimalType(_)) => {
+      match (decimal_of(left), decimal_of(right)) {
+        (Some(l), Some(r)) => Operands::Decimals(l, r),
+        _ => return Err(RloxError::InterpreterError("Number too large for decimal.".to_string())),
+      }
+    }
+    (RloxType::BigIntType(_), _) | (_, RloxType::BigIntType(_)) => {
+      match (bigint_of(left), bigint_of(right)) {
+        (Some(l), Some(r)) => Operands::BigInts(l, r),
+        _ => return Err(RloxError::InterpreterError(format!("unsupported operand type(s) for {}: both operand types must be number", token_type.name()))),
+      }
+    }
+    _ => return Err(RloxError::InterpreterError(format!("unsupported operand type(s) for {}: both operand types must be number", token_type.name()))),
+  };
+
+  Ok(operands)
+}
+
+fn comparison(token_type: &TokenType) -> Option<&'static [Ordering]> {
+  match token_type {
+    TokenType::GREATER => Some(&[Ordering::Greater]),
+    TokenType::GREATEREQUAL => Some(&[Ordering::Greater, Ordering::Equal]),
+    TokenType::LESS => Some(&[Ordering::Less]),
+    TokenType::LESSEQUAL => Some(&[Ordering::Less, Ordering::Equal]),
+    _ => None,
+  }
+}
+
+// Exact, converting the integer to float could round it.
+fn compare_integer_to_float(integer: i64, float: f64) -> Option<Ordering> {
+  if float.is_nan() {
+    return None;
+  }
+
+  let whole = float.trunc();
+  match (integer as i128).cmp(&(whole as i128)) {
+    Ordering::Equal => 0.0.partial_cmp(&(float - whole)),
+    ordering => Some(ordering),
+  }
+}
+
+fn integer_binary(token_type: &TokenType, left: i64, right: i64) -> Result<RloxType, RloxError> {
+  let result = match token_type {
+    TokenType::PLUS => left.checked_add(right),
+    TokenType::MINUS => left.checked_sub(right),
+    TokenType::STAR => left.checked_mul(right),
+    TokenType::SLASH => {
+      if right == 0 {
+        return Err(division_by_zero());
+      }
+      left.checked_div(right)
+    }
+    TokenType::PERCENT => {
+      if right == 0 {
+        return Err(division_by_zero());
+      }
+      left.checked_rem(right)
+    }
+    TokenType::DIV => {
+      if right == 0 {
+        return Err(division_by_zero());
+      }
+      left.checked_div(right).map(|_| Integer::div_floor(&left, &right))
+    }
+    TokenType::STARSTAR => {
+      if right < 0 {
+        return Ok(RloxType::NumberType((left as f64).powf(right as f64)));
+      }
+      right.to_u32().and_then(|e| left.checked_pow(e))
+    }
+    TokenType::AMPERSAND => Some(left & right),
+    TokenType::PIPE => Some(left | right),
+    TokenType::CARET => Some(left ^ right),
+    TokenType::LESSLESS => Some(left << shift_count(right, 64)?),
+    TokenType::GREATERGREATER => Some(left >> shift_count(right, 64)?),
+    _ => return Err(RloxError::InterpreterError(format!("unimplemented operand {}", token_type.name()))),
+  };
+
+  result.map(RloxType::IntegerType).ok_or_else(overflow)
+}
+
+fn bigint_binary(token_type: &TokenType, left: BigInt, right: BigInt) -> Result<RloxType, RloxError> {
+  let result = match token_type {
+    TokenType::PLUS => left + right,
+    TokenType::MINUS => left - right,
+    TokenType::STAR => left * right,
+    TokenType::SLASH => {
+      if right.is_zero() {
+        return Err(division_by_zero());
+      }
+      left / right
+    }
+    TokenType::PERCENT => {
+      if right.is_zero() {
+        return Err(division_by_zero());
+      }
+      left % right
+    }
+    TokenType::DIV => {
+      if right.is_zero() {
+        return Err(division_by_zero());
+      }
+      left.div_floor(&right)
+    }
+    TokenType::STARSTAR => {
+      if right.is_negative() {
+        return Err(RloxError::InterpreterError("Negative exponent for bigint.".to_string()));
+      }
+      match right.to_u32() {
+        Some(e) => num_traits::pow(left, e as usize),
+        None => return Err(RloxError::InterpreterError("Exponent too large.".to_string())),
+      }
+    }
+    TokenType::AMPERSAND => left & right,
+    TokenType::PIPE => left | right,
+    TokenType::CARET => left ^ right,
+    TokenType::LESSLESS => left << bigint_shift_count(&right)?,
+    TokenType::GREATERGREATER => left >> bigint_shift_count(&right)?,
+    _ => return Err(RloxError::InterpreterError(format!("unimplemented operand {}", token_type.name()))),
+  };
+
+  Ok(RloxType::BigIntType(result))
+}
+
+fn decimal_binary(token_type: &TokenType, left: Decimal, right: Decimal) -> Result<RloxType, RloxError> {
+  let result = match token_type {
+    TokenType::PLUS => left.checked_add(right),
+    TokenType::MINUS => left.checked_sub(right),
+    TokenType::STAR => left.checked_mul(right),
+    TokenType::SLASH => {
+      if right.is_zero() {
+        return Err(division_by_zero());
+      }
+      left.checked_div(right)
+    }
+    TokenType::PERCENT => {
+      if right.is_zero() {
+        return Err(division_by_zero());
+      }
+      left.checked_rem(right)
+    }
+    TokenType::DIV => {
+      if right.is_zero() {
+        return Err(division_by_zero());
+      }
+      left.checked_div(right).map(|d| d.floor())
+    }
+    TokenType::STARSTAR => decimal_pow(left, right)?,
+    _ => return Err(integers_only(token_type)),
+  };
+
+  result.map(RloxType::DecimalType).ok_or_else(|| RloxError::InterpreterError("Decimal overflow.".to_string()))
+}
+
+fn float_binary(token_type: &TokenType, left: f64, right: f64) -> Result<RloxType, RloxError> {
+  match token_type {
+    TokenType::PLUS => Ok(RloxType::NumberType(left + right)),
+    TokenType::MINUS => Ok(RloxType::NumberType(left - right)),
+    TokenType::STAR => Ok(RloxType::NumberType(left * right)),
+    TokenType::SLASH => Ok(RloxType::NumberType(left / right)),
+    TokenType::PERCENT => Ok(RloxType::NumberType(left % right)),
+    TokenType::DIV => Ok(RloxType::NumberType((left / right).floor())),
+    TokenType::STARSTAR => Ok(RloxType::NumberType(left.powf(right))),
+    _ => Err(integers_only(token_type)),
+  }
+}
+
+// Decimals are only raised to whole powers, which keeps the result exact.
+fn decimal_pow(base: Decimal, exponent: Decimal) -> Result<Option<Decimal>, RloxError> {
+  let exponent = match exponent.fract().is_zero().then(|| exponent.to_i64()).flatten() {
+    Some(e) => e,
+    None => return Err(RloxError::InterpreterError("Decimal exponent must be a whole number.".to_string())),
+  };
+
+  let mut result = Some(Decimal::one());
+  let mut square = Some(base);
+  let mut e = exponent.unsigned_abs();
+  while e > 0 {
+    if e & 1 == 1 {
+      result = result.zip(square).and_then(|(r, s)| r.checked_mul(s));
+    }
+    e >>= 1;
+    if e > 0 {
+      square = square.and_then(|s| s.checked_mul(s));
+    }
+  }
+
+  if exponent < 0 {
+    if base.is_zero() {
+      return Err(division_by_zero());
+    }
+    return Ok(result.and_then(|r| Decimal::one().checked_div(r)));
+  }
+
+  Ok(result)
+}
+
+fn shift_count(count: i64, bits: i64) -> Result<u32, RloxError> {
+  if (0..bits).contains(&count) {
+    Ok(count as u32)
+  } else {
+    Err(shift_out_of_range())
+  }
+}
+
+fn bigint_shift_count(count: &BigInt) -> Result<u32, RloxError> {
+  match count.to_u32() {
+    Some(c) if !count.is_negative() => Ok(c),
+    _ => Err(shift_out_of_range()),
+  }
+}
+
+fn shift_out_of_range() -> RloxError {
+  RloxError::InterpreterError("Shift count out of range.".to_string())
+}
+
+fn integers_only(token_type: &TokenType) -> RloxError {
+  RloxError::InterpreterError(format!("unsupported operand type(s) for {}: both operand types must be integer", token_type.name()))
+}
+
+fn overflow() -> RloxError {
+  RloxError::InterpreterError("Integer overflow.".to_string())
+}
+
+fn division_by_zero() -> RloxError {
+  RloxError::InterpreterError("Division by zero.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn int(i: i64) -> RloxType {
+    RloxType::IntegerType(i)
+  }
+
+  fn float(n: f64) -> RloxType {
+    RloxType::NumberType(n)
+  }
+
+  #[test]
+  fn test_promotion() -> Result<(), RloxError> {
+    assert!(matches!(binary(&TokenType::PLUS, &int(1), &int(2))?, RloxType::IntegerType(3)));
+    assert!(matches!(binary(&TokenType::PLUS, &int(1), &float(0.5))?, RloxType::NumberType(n) if n == 1.5));
+    assert!(matches!(binary(&TokenType::STAR, &float(2.0), &int(3))?, RloxType::NumberType(n) if n == 6.0));
+    Ok(())
+  }
+
+  #[test]
+  fn test_integer_division() -> Result<(), RloxError> {
+    assert!(matches!(binary(&TokenType::SLASH, &int(7), &int(2))?, RloxType::IntegerType(3)));
+    assert!(matches!(binary(&TokenType::SLASH, &int(-7), &int(2))?, RloxType::IntegerType(-3)));
+    assert!(matches!(binary(&TokenType::SLASH, &int(7), &float(2.0))?, RloxType::NumberType(n) if n == 3.5));
+    assert_eq!(binary(&TokenType::SLASH, &int(1), &int(0)).unwrap_err().to_string(), "Division by zero.");
+    Ok(())
+  }
+
+  #[test]
+  fn test_modulo_and_floor_division() -> Result<(), RloxError> {
+    let test_input = [
+      (TokenType::PERCENT, 7, 3, 1),
+      (TokenType::PERCENT, -7, 3, -1),
+      (TokenType::DIV, 7, 2, 3),
+      (TokenType::DIV, -7, 2, -4),
+      (TokenType::DIV, 7, -2, -4),
+    ];
+
+    for (token_type, left, right, expected) in test_input.iter() {
+      assert_eq!(binary(token_type, &int(*left), &int(*right))?, int(*expected));
+    }
+    assert!(matches!(binary(&TokenType::DIV, &float(7.5), &int(2))?, RloxType::NumberType(n) if n == 3.0));
+    assert_eq!(binary(&TokenType::PERCENT, &int(1), &int(0)).unwrap_err().to_string(), "Division by zero.");
+    Ok(())
+  }
+
+  #[test]
+  fn test_power() -> Result<(), RloxError> {
+    assert_eq!(binary(&TokenType::STARSTAR, &int(2), &int(10))?, int(1024));
+    assert!(matches!(binary(&TokenType::STARSTAR, &int(2), &int(-1))?, RloxType::NumberType(n) if n == 0.5));
+    assert_eq!(binary(&TokenType::STARSTAR, &int(2), &int(64)).unwrap_err().to_string(), "Integer overflow.");
+    Ok(())
+  }
+
+  #[test]
+  fn test_bitwise() -> Result<(), RloxError> {
+    let test_input = [
+      (TokenType::AMPERSAND, 6, 3, 2),
+      (TokenType::PIPE, 6, 3, 7),
+      (TokenType::CARET, 6, 3, 5),
+      (TokenType::LESSLESS, 1, 4, 16),
+      (TokenType::GREATERGREATER, -16, 2, -4),
+    ];
+
+    for (token_type, left, right, expected) in test_input.iter() {
+      assert_eq!(binary(token_type, &int(*left), &int(*right))?, int(*expected));
+    }
+    assert_eq!(invert(&int(5))?, int(-6));
+    assert_eq!(binary(&TokenType::LESSLESS, &int(1), &int(64)).unwrap_err().to_string(), "Shift count out of range.");
+    assert_eq!(
+      binary(&TokenType::AMPERSAND, &float(1.5), &int(1)).unwrap_err().to_string(),
+      "unsupported operand type(s) for AMPERSAND: both operand types must be integer"
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_overflow() {
+    let test_input = [
+      (TokenType::PLUS, i64::MAX, 1),
+      (TokenType::MINUS, i64::MIN, 1),
+      (TokenType::STAR, i64::MAX, 2),
+      (TokenType::SLASH, i64::MIN, -1),
+    ];
+
+    for (token_type, left, right) in test_input.iter() {
+      assert_eq!(binary(token_type, &int(*left), &int(*right)).unwrap_err().to_string(), "Integer overflow.");
+    }
+    assert_eq!(negate(&int(i64::MIN)).unwrap_err().to_string(), "Integer overflow.");
+  }
+
+  #[test]
+  fn test_compare() {
+    assert_eq!(compare(&int(1), &float(1.0)), Some(Ordering::Equal));
+    assert_eq!(compare(&int(1), &float(1.5)), Some(Ordering::Less));
+    assert_eq!(compare(&float(-0.5), &int(0)), Some(Ordering::Less));
+    // 2^53 + 1 isn't representable as float.
+    assert_eq!(compare(&int(9007199254740993), &float(9007199254740992.0)), Some(Ordering::Greater));
+    assert_eq!(compare(&int(1), &float(f64::NAN)), None);
+  }
+
+  fn bigint(s: &str) -> RloxType {
+    RloxType::BigIntType(BigInt::from_str(s).unwrap())
+  }
+
+  fn decimal(s: &str) -> RloxType {
+    RloxType::DecimalType(Decimal::from_str(s).unwrap())
+  }
+
+  #[test]
+  fn test_bigint_arithmetic() -> Result<(), RloxError> {
+    assert_eq!(binary(&TokenType::STAR, &int(i64::MAX), &bigint("2"))?, bigint("18446744073709551614"));
+    assert_eq!(binary(&TokenType::SLASH, &bigint("-7"), &int(2))?, bigint("-3"));
+    assert_eq!(binary(&TokenType::SLASH, &bigint("1"), &bigint("0")).unwrap_err().to_string(), "Division by zero.");
+    assert_eq!(binary(&TokenType::STARSTAR, &bigint("2"), &int(100))?, bigint("1267650600228229401496703205376"));
+    assert_eq!(binary(&TokenType::LESSLESS, &bigint("1"), &int(70))?, bigint("1180591620717411303424"));
+    assert_eq!(binary(&TokenType::DIV, &bigint("-7"), &int(2))?, bigint("-4"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_decimal_arithmetic() -> Result<(), RloxError> {
+    assert_eq!(binary(&TokenType::PLUS, &decimal("0.1"), &decimal("0.2"))?.to_string(), "0.3");
+    assert_eq!(binary(&TokenType::STAR, &decimal("19.99"), &int(3))?.to_string(), "59.97");
+    assert_eq!(binary(&TokenType::PLUS, &decimal("0.5"), &bigint("1"))?.to_string(), "1.5");
+    assert_eq!(binary(&TokenType::SLASH, &decimal("1"), &decimal("4"))?.to_string(), "0.25");
+    assert_eq!(binary(&TokenType::STARSTAR, &decimal("1.5"), &int(2))?.to_string(), "2.25");
+    assert_eq!(binary(&TokenType::STARSTAR, &decimal("2"), &int(-2))?.to_string(), "0.25");
+    assert_eq!(binary(&TokenType::PERCENT, &decimal("5.5"), &int(2))?.to_string(), "1.5");
+    Ok(())
+  }
+
+  #[test]
+  fn test_exact_numbers_dont_mix_with_floats() {
+    assert_eq!(
+      binary(&TokenType::PLUS, &decimal("0.1"), &float(0.2)).unwrap_err().to_string(),
+      "unsupported operand type(s) for PLUS: can't mix decimal and number, convert with number() or decimal()"
+    );
+    assert!(binary(&TokenType::LESS, &bigint("1"), &float(0.2)).is_err());
+  }
+
+  #[test]
+  fn test_conversions() -> Result<(), RloxError> {
+    assert_eq!(to_decimal(&float(0.1))?.to_string(), "0.1");
+    assert_eq!(to_bigint(&RloxType::StringType("123456789012345678901234567890".to_string()))?, bigint("123456789012345678901234567890"));
+    assert_eq!(to_number(&decimal("2.5"))?, float(2.5));
+    assert_eq!(to_bigint(&decimal("1.5")).unwrap_err().to_string(), "Can't convert 1.5m to bigint.");
+    assert_eq!(compare(&bigint("10"), &decimal("9.5")), Some(Ordering::Greater));
+    Ok(())
+  }
+}
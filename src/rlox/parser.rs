@@ -33,20 +33,64 @@ impl Parser {
   }
 
   fn assignment<T: 'static>(&self) -> ParserExprResult<T> {
-    let expr = self.or()?;
-
-    if self.token_match(vec![TokenType::EQUAL]) {
+    let expr = self.conditional()?;
+
+    if self.token_match(vec![
+      TokenType::EQUAL,
+      TokenType::PLUSEQUAL,
+      TokenType::MINUSEQUAL,
+      TokenType::STAREQUAL,
+      TokenType::SLASHEQUAL,
+      TokenType::PERCENTEQUAL,
+    ]) {
       let equals = self.previous();
       let value = self.assignment()?;
 
-      if let Some(var_expr) = expr.borrow().as_any().downcast_ref::<Variable>() {
-        let name = var_expr.name.clone();
-        return Ok(Rc::new(RefCell::new(Assign::new(name, value))));
-      } else if let Some(get_expr) = expr.borrow().as_any().downcast_ref::<Get<T>>() {
-        return Ok(Rc::new(RefCell::new(Set::new(get_expr.object.clone(), get_expr.name.clone(), value))));
-      }
+      return self.assignment_target(expr, equals, value, false);
+    }
+
+    Ok(expr)
+  }
+
+  // Compound assignments and increments keep their operator, the target is
+  // still only evaluated once by the interpreter.
+  fn assignment_target<T: 'static>(&self, target: Exp<T>, operator: Token, value: Exp<T>, postfix: bool) -> ParserExprResult<T> {
+    if let Some(var_expr) = target.borrow().as_any().downcast_ref::<Variable>() {
+      let name = var_expr.name.clone();
+      return Ok(Rc::new(RefCell::new(Assign::new(name, operator, value, postfix))));
+    } else if let Some(get_expr) = target.borrow().as_any().downcast_ref::<Get<T>>() {
+      return Ok(Rc::new(RefCell::new(Set::new(get_expr.object.clone(), get_expr.name.clone(), operator, value, postfix))));
+    }
+
+    Err(RloxError::ParserError(format!("{} Invalid assignment target.", operator.lexeme)))
+  }
+
+  // ++x and x++ add the literal 1.
+  fn increment<T: 'static>(&self, target: Exp<T>, operator: Token, postfix: bool) -> ParserExprResult<T> {
+    let one = Rc::new(RefCell::new(LiteralObj::new(Some(Literal::IntegerType(1)))));
+    self.assignment_target(target, operator, one, postfix)
+  }
+
+  fn conditional<T: 'static>(&self) -> ParserExprResult<T> {
+    let condition = self.coalesce()?;
 
-      return Err(RloxError::ParserError(format!("{} Invalid assignment target.", equals.lexeme)))
+    if self.token_match(vec![TokenType::QUESTION]) {
+      let then_branch = self.expression()?;
+      self.consume(TokenType::COLON, "Expect ':' after then branch of conditional expression.")?;
+      let else_branch = self.conditional()?;
+      return Ok(Rc::new(RefCell::new(Conditional::new(condition, then_branch, else_branch))));
+    }
+
+    Ok(condition)
+  }
+
+  fn coalesce<T: 'static>(&self) -> ParserExprResult<T> {
+    let mut expr = self.or()?;
+
+    while self.token_match(vec![TokenType::QUESTIONQUESTION]) {
+      let operator = self.previous();
+      let right = self.or()?;
+      expr = Rc::new(RefCell::new(Logical::new(expr, operator, right)));
     }
 
     Ok(expr)
@@ -106,6 +150,13 @@ impl Parser {
     self.peek().token_type == token_type
   }
 
+  fn check_next(&self, token_type: TokenType) -> bool {
+    match self.tokens.get(self.current.get() + 1) {
+      Some(token) => token.token_type == token_type,
+      None => false,
+    }
+  }
+
   fn advance(&self) -> Token {
     if !self.is_at_end() {
       self.current.set(self.current.get() + 1);
@@ -155,9 +206,57 @@ impl Parser {
   }
 
   fn multiplication<T: 'static>(&self) -> ParserExprResult<T> {
+    let mut expr = self.bitwise_or()?;
+
+    while self.token_match(vec![TokenType::SLASH, TokenType::STAR, TokenType::PERCENT, TokenType::DIV]) {
+      let operator = self.previous();
+      let right = self.bitwise_or()?;
+      expr = Rc::new(RefCell::new(Binary::new(expr, operator.clone(), right)));
+    }
+
+    Ok(expr)
+  }
+
+  fn bitwise_or<T: 'static>(&self) -> ParserExprResult<T> {
+    let mut expr = self.bitwise_xor()?;
+
+    while self.token_match(vec![TokenType::PIPE]) {
+      let operator = self.previous();
+      let right = self.bitwise_xor()?;
+      expr = Rc::new(RefCell::new(Binary::new(expr, operator.clone(), right)));
+    }
+
+    Ok(expr)
+  }
+
+  fn bitwise_xor<T: 'static>(&self) -> ParserExprResult<T> {
+    let mut expr = self.bitwise_and()?;
+
+    while self.token_match(vec![TokenType::CARET]) {
+      let operator = self.previous();
+      let right = self.bitwise_and()?;
+      expr = Rc::new(RefCell::new(Binary::new(expr, operator.clone(), right)));
+    }
+
+    Ok(expr)
+  }
+
+  fn bitwise_and<T: 'static>(&self) -> ParserExprResult<T> {
+    let mut expr = self.shift()?;
+
+    while self.token_match(vec![TokenType::AMPERSAND]) {
+      let operator = self.previous();
+      let right = self.shift()?;
+      expr = Rc::new(RefCell::new(Binary::new(expr, operator.clone(), right)));
+    }
+
+    Ok(expr)
+  }
+
+  fn shift<T: 'static>(&self) -> ParserExprResult<T> {
     let mut expr = self.unary()?;
 
-    while self.token_match(vec![TokenType::SLASH, TokenType::STAR]) {
+    while self.token_match(vec![TokenType::LESSLESS, TokenType::GREATERGREATER]) {
       let operator = self.previous();
       let right = self.unary()?;
       expr = Rc::new(RefCell::new(Binary::new(expr, operator.clone(), right)));
@@ -167,29 +266,70 @@ impl Parser {
   }
 
   fn unary<T: 'static>(&self) -> ParserExprResult<T> {
-    if self.token_match(vec![TokenType::BANG, TokenType::MINUS]) {
+    if self.token_match(vec![TokenType::PLUSPLUS, TokenType::MINUSMINUS]) {
+      let operator = self.previous();
+      let target = self.unary()?;
+      return self.increment(target, operator, false);
+    }
+
+    if self.token_match(vec![TokenType::BANG, TokenType::MINUS, TokenType::TILDE]) {
       let operator = self.previous();
       let right = self.unary()?;
       return Ok(Rc::new(RefCell::new(Unary::new(operator.clone(), right))));
     }
 
-    self.call()
+    self.exponent()
+  }
+
+  // Right associative and binds tighter than unary minus, -2 ** 2 is -4.
+  fn exponent<T: 'static>(&self) -> ParserExprResult<T> {
+    let expr = self.postfix()?;
+
+    if self.token_match(vec![TokenType::STARSTAR]) {
+      let operator = self.previous();
+      let right = self.unary()?;
+      return Ok(Rc::new(RefCell::new(Binary::new(expr, operator.clone(), right))));
+    }
+
+    Ok(expr)
+  }
+
+  fn postfix<T: 'static>(&self) -> ParserExprResult<T> {
+    let expr = self.call()?;
+
+    if self.token_match(vec![TokenType::PLUSPLUS, TokenType::MINUSMINUS]) {
+      let operator = self.previous();
+      return self.increment(expr, operator, true);
+    }
+
+    Ok(expr)
   }
 
   fn call<T: 'static>(&self) -> ParserExprResult<T> {
     let mut expr = self.primary()?;
+    let mut optional = false;
 
     loop {
       if self.token_match(vec![TokenType::LEFTPAREN]) {
         expr = self.finish_call(expr)?;
       } else if self.token_match(vec![TokenType::DOT]) {
         let name = self.consume(TokenType::IDENTIFIER, "Expect property name after '.'.")?;
-        expr = Rc::new(RefCell::new(Get::new(expr, name)));
+        expr = Rc::new(RefCell::new(Get::new(expr, name, false)));
+      } else if self.token_match(vec![TokenType::QUESTIONDOT]) {
+        let name = self.consume(TokenType::IDENTIFIER, "Expect property name after '?.'.")?;
+        expr = Rc::new(RefCell::new(Get::new(expr, name, true)));
+        optional = true;
       } else {
         break;
       }
     }
 
+    // A nil before ?. skips the rest of the chain, a?.b.c() is nil when a
+    // is nil.
+    if optional {
+      expr = Rc::new(RefCell::new(OptionalChain::new(expr)));
+    }
+
     return Ok(expr)
   }
 
@@ -238,6 +378,10 @@ impl Parser {
       ))));
     }
 
+    if self.token_match(vec![TokenType::INTERPOLATION]) {
+      return self.interpolation();
+    }
+
     if self.token_match(vec![TokenType::SUPER]) {
       let keyword = self.previous();
       self.consume(TokenType::DOT, "Expect '.' after 'super'.")?;
@@ -262,6 +406,25 @@ impl Parser {
     Err(RloxError::ParserError("Expect expression.".to_string()))
   }
 
+  // The scanner splits "a${b}c" into INTERPOLATION("a") b STRING("c").
+  fn interpolation<T: 'static>(&self) -> ParserExprResult<T> {
+    let mut parts: Vec<Exp<T>> = vec![Rc::new(RefCell::new(LiteralObj::new(self.previous().literal)))];
+
+    loop {
+      parts.push(self.expression()?);
+
+      if self.token_match(vec![TokenType::INTERPOLATION]) {
+        parts.push(Rc::new(RefCell::new(LiteralObj::new(self.previous().literal))));
+      } else {
+        let end = self.consume(TokenType::STRING, "Expect '}' after interpolated expression.")?;
+        parts.push(Rc::new(RefCell::new(LiteralObj::new(end.literal))));
+        break;
+      }
+    }
+
+    Ok(Rc::new(RefCell::new(Interpolation::new(parts))))
+  }
+
   fn consume(&self, token_type: TokenType, message: &str) -> Result<Token, RloxError> {
     if self.check(token_type) {
       return Ok(self.advance());
@@ -436,6 +599,13 @@ impl Parser {
       return self.var_declaration();
     }
 
+    // "test" is only a keyword when followed by the test name, so it
+    // can still be used as an ordinary identifier.
+    if self.check(TokenType::IDENTIFIER) && self.peek().lexeme == "test" && self.check_next(TokenType::STRING) {
+      self.advance();
+      return self.test_declaration();
+    }
+
     self.statement()
   }
 
@@ -488,6 +658,14 @@ impl Parser {
     Ok(Rc::new(RefCell::new(Var::new(name, initializer))))
   }
 
+  fn test_declaration<T: 'static>(&self) -> ParserStmtResult<T> {
+    let name = self.consume(TokenType::STRING, "Expect test name.")?;
+    self.consume(TokenType::LEFTBRACE, "Expect '{' before test body.")?;
+    let body = self.block()?;
+
+    Ok(Rc::new(RefCell::new(Test::new(name, body))))
+  }
+
   fn class_declaration<T: 'static>(&self) -> ParserStmtResult<T> {
     let name = self.consume(TokenType::IDENTIFIER, "Expect class name.")?;
 
@@ -500,13 +678,62 @@ impl Parser {
     self.consume(TokenType::LEFTBRACE, "Expect '{' before class body.")?;
 
     let mut methods = Vec::new();
+    let mut class_methods = Vec::new();
+    let mut fields = Vec::new();
+    let mut getters = Vec::new();
+    let mut setters = Vec::new();
     while !self.check(TokenType::RIGHTBRACE) && !self.is_at_end() {
-      methods.push(self.function("method")?);
+      // Members prefixed with "class" live on the class itself.
+      if self.check(TokenType::CLASS) && self.check_next(TokenType::IDENTIFIER) {
+        self.advance();
+        if self.check_next(TokenType::LEFTPAREN) {
+          class_methods.push(self.function("method")?);
+        } else {
+          fields.push(self.class_field()?);
+        }
+      } else if self.check(TokenType::IDENTIFIER) && self.check_next(TokenType::LEFTBRACE) {
+        getters.push(self.getter()?);
+      } else if self.peek().lexeme == "set" && self.check_next(TokenType::IDENTIFIER) {
+        self.advance();
+        setters.push(self.setter()?);
+      } else {
+        methods.push(self.function("method")?);
+      }
     }
 
     self.consume(TokenType::RIGHTBRACE, "Expect '}' after class body.")?;
 
-    Ok(Rc::new(RefCell::new(Class::new(name, superclass, methods))))
+    Ok(Rc::new(RefCell::new(Class::new(name, superclass, methods, class_methods, fields, getters, setters))))
+  }
+
+  // Getters are methods without parameters called on property access.
+  fn getter<T: 'static>(&self) -> ParserStmtResult<T> {
+    let name = self.consume(TokenType::IDENTIFIER, "Expect getter name.")?;
+    self.consume(TokenType::LEFTBRACE, "Expect '{' before getter body.")?;
+    let body = self.block()?;
+    Ok(Rc::new(RefCell::new(Function::new(name, Vec::new(), body))))
+  }
+
+  fn setter<T: 'static>(&self) -> ParserStmtResult<T> {
+    let setter = self.function("setter")?;
+    if let Some(function) = setter.borrow().as_any().downcast_ref::<Function<T>>() {
+      if function.params.len() != 1 {
+        return Err(RloxError::ParserError(format!("Setter '{}' must have exactly one parameter.", function.name.lexeme)));
+      }
+    }
+    Ok(setter)
+  }
+
+  fn class_field<T: 'static>(&self) -> ParserStmtResult<T> {
+    let name = self.consume(TokenType::IDENTIFIER, "Expect field name.")?;
+
+    let mut initializer: ParserExpr<T> = Rc::new(RefCell::new(LiteralObj::new(Some(Literal::NullType))));
+    if self.token_match(vec![TokenType::EQUAL]) {
+      initializer = self.expression()?;
+    }
+
+    self.consume(TokenType::SEMICOLON, "Expect ';' after class field.")?;
+    Ok(Rc::new(RefCell::new(Var::new(name, initializer))))
   }
 
   pub fn parse<T: 'static>(&self) -> Result<Vec<ParserStmt<T>>, RloxError> {
@@ -0,0 +1,144 @@
+// The Regex namespace, Regex.compile(pattern) returns a pattern object
+// with test, match, findAll, replace and split. Patterns use the syntax of
+// the regex crate, matching runs in linear time.
+use std::fmt;
+use ::regex::Regex;
+use super::{
+  callable::Callable,
+  interpreter::Interpreter,
+  native_object::NativeObject,
+  rlox_type::RloxType,
+  rlox_errors::RloxError,
+  rlox_list::RloxList,
+};
+
+pub fn namespace() -> RloxType {
+  RloxType::NativeType(NativeObject::new("Regex", vec![
+    ("compile", RloxType::CallableType(Box::new(Compile {}))),
+  ]))
+}
+
+fn string_argument<'a>(name: &str, value: &'a RloxType) -> Result<&'a str, RloxError> {
+  match value {
+    RloxType::StringType(s) => Ok(s),
+    _ => Err(RloxError::InterpreterError(format!("{}() expects a string, not {}.", name, value.type_name()))),
+  }
+}
+
+fn list(items: Vec<RloxType>) -> RloxType {
+  RloxType::ListType(RloxList::new(items))
+}
+
+struct Pattern(Regex);
+
+impl fmt::Display for Pattern {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "/{}/", self.0.as_str())
+  }
+}
+
+#[derive(Clone)]
+struct Compile {}
+
+impl Compile {
+  // The regex crate explains the error on its last line, after "error: ".
+  fn error(pattern: &str, line: usize, e: ::regex::Error) -> RloxError {
+    let text = e.to_string();
+    let reason = text.lines().last().map(|l| l.trim_start_matches("error: ")).unwrap_or("invalid pattern");
+    RloxError::InterpreterError(format!("Invalid regex '{}' at line {}: {}.", pattern, line, reason))
+  }
+}
+
+impl Callable for Compile {
+  fn arity(&self) -> usize {
+    1
+  }
+
+  fn call(&self, interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let pattern = string_argument("Regex.compile", &arguments[0])?;
+    let regex = Regex::new(pattern).map_err(|e| Compile::error(pattern, interpreter.line(), e))?;
+
+    let method = |name: &'static str, arity: usize| {
+      (name, RloxType::CallableType(Box::new(RegexMethod { regex: regex.clone(), name, arity })))
+    };
+
+    Ok(RloxType::NativeType(NativeObject::new("Regex", vec![
+      method("test", 1),
+      method("match", 1),
+      method("findAll", 1),
+      method("replace", 2),
+      method("split", 1),
+    ]).with_value(Pattern(regex.clone()))))
+  }
+
+  fn name(&self) -> String {
+    String::from("<native Regex.compile function>")
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+struct RegexMethod {
+  regex: Regex,
+  name: &'static str,
+  arity: usize,
+}
+
+impl Callable for RegexMethod {
+  fn arity(&self) -> usize {
+    self.arity
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let text = string_argument(self.name, &arguments[0])?;
+
+    match self.name {
+      "test" => Ok(RloxType::BooleanType(self.regex.is_match(text))),
+      // The whole match followed by the groups, nil for groups that
+      // didn't take part, nil when nothing matches.
+      "match" => Ok(self.regex.captures(text).map_or(RloxType::NullType, |captures| {
+        list(captures.iter()
+          .map(|group| group.map_or(RloxType::NullType, |m| RloxType::StringType(m.as_str().to_string())))
+          .collect())
+      })),
+      "findAll" => Ok(list(self.regex.find_iter(text).map(|m| RloxType::StringType(m.as_str().to_string())).collect())),
+      // $1 or ${name} in the replacement refer to groups, $$ is a dollar.
+      "replace" => {
+        let replacement = string_argument("replace", &arguments[1])?;
+        Ok(RloxType::StringType(self.regex.replace_all(text, replacement).into_owned()))
+      }
+      "split" => Ok(list(self.regex.split(text).map(|part| RloxType::StringType(part.to_string())).collect())),
+      _ => Err(RloxError::InterpreterError(format!("Undefined property '{}' for Regex.", self.name))),
+    }
+  }
+
+  fn name(&self) -> String {
+    format!("<native Regex.{} method>", self.name)
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_compile_errors() {
+    let cases = [
+      ("(unclosed", "Invalid regex '(unclosed' at line 3: unclosed group."),
+      ("a{2,1}", "Invalid regex 'a{2,1}' at line 3: invalid repetition count range, the start must be <= the end."),
+      ("\\q", "Invalid regex '\\q' at line 3: unrecognized escape sequence."),
+    ];
+
+    for (pattern, expected) in cases.iter() {
+      let e = Regex::new(pattern).unwrap_err();
+      assert_eq!(Compile::error(pattern, 3, e).to_string(), *expected);
+    }
+  }
+}
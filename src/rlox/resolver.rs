@@ -23,11 +23,21 @@ enum ClassType {
   None,
   Class,
   SubClass,
+  // Class methods and fields, they have no 'this'.
+  Static,
+}
+
+// Locals get slots in the order they are declared in, the interpreter
+// defines them in the same order.
+#[derive(Clone)]
+struct Local {
+  defined: bool,
+  slot: usize,
 }
 
 #[derive(Clone)]
 pub struct Resolver {
-  scopes: Rc<RefCell<Vec<HashMap<String, bool>>>>,
+  scopes: Rc<RefCell<Vec<HashMap<String, Local>>>>,
   interpreter: Interpreter,
   current_function: Rc<RefCell<FunctionType>>,
   current_class: Rc<RefCell<ClassType>>,
@@ -77,7 +87,8 @@ impl<'a> Resolver {
       if back_scope.contains_key(&name.lexeme) {
         return Err(RloxError::ResolverError("Already variable with this name in this scope.".to_string()));
       }
-      back_scope.insert(name.lexeme, false);
+      let slot = back_scope.len();
+      back_scope.insert(name.lexeme, Local { defined: false, slot });
     }
 
     Ok(())
@@ -89,16 +100,16 @@ impl<'a> Resolver {
       return;
     }
 
-    if let Some(back_scope) = scopes.last_mut() {
-      back_scope.insert(name.lexeme, true);
+    if let Some(local) = scopes.last_mut().and_then(|scope| scope.get_mut(&name.lexeme)) {
+      local.defined = true;
     }
   }
 
   fn resolve_local(&self, var_expr: VarExpr, name: Token) {
     let scopes = self.scopes.borrow();
     for (i, scope) in scopes.iter().enumerate().rev() {
-      if scope.contains_key(&name.lexeme) {
-        self.interpreter.resolve(var_expr, scopes.len() - 1 - i);
+      if let Some(local) = scope.get(&name.lexeme) {
+        self.interpreter.resolve(var_expr, scopes.len() - 1 - i, local.slot);
         return;
       }
     }
@@ -193,6 +204,18 @@ impl super::stmt::Visitor<RloxType> for Resolver {
     Ok(RloxType::NullType)
   }
 
+  fn visit_test_stmt(&self, stmt: &Test<RloxType>) -> Result<RloxType, RloxError> {
+    if !self.scopes.borrow().is_empty() {
+      return Err(RloxError::ResolverError("Tests can only be declared at top level.".to_string()));
+    }
+
+    self.begin_scope();
+    self.resolve_statements(stmt.body.clone())?;
+    self.end_scope();
+
+    Ok(RloxType::NullType)
+  }
+
   fn visit_while_stmt(&self, stmt: &While<RloxType>) -> Result<RloxType, RloxError> {
     self.resolve_expr(stmt.condition.clone())?;
     self.resolve_stmt(stmt.body.clone())?;
@@ -212,21 +235,40 @@ impl super::stmt::Visitor<RloxType> for Resolver {
       self.current_class.replace(ClassType::SubClass);
       self.resolve_expr(Rc::new(RefCell::new(superclass)))?;
     }
+    let class_type = self.current_class.replace(ClassType::Static);
+
+    // Class fields are evaluated where the class is declared.
+    for field in &stmt.fields {
+      if let Some(var) = field.borrow().as_any().downcast_ref::<Var<RloxType>>() {
+        self.resolve_expr(var.initializer.clone())?;
+      } else {
+        return Err(RloxError::ResolverError("Expected class field.".to_string()));
+      }
+    }
 
     if let Some(_superklass) = &stmt.superclass {
       self.begin_scope();
       let mut scopes = self.scopes.borrow_mut();
       if let Some(back_scope) = scopes.last_mut() {
-        back_scope.insert("super".to_string(), true);
+        back_scope.insert("super".to_string(), Local { defined: true, slot: 0 });
       }
     }
 
+    for method in &stmt.class_methods {
+      if let Some(func_method) = method.borrow().as_any().downcast_ref::<Function<RloxType>>() {
+        self.resolve_function(func_method, FunctionType::Function)?;
+      } else {
+        return Err(RloxError::ResolverError("Expected method.".to_string()));
+      }
+    }
+    self.current_class.replace(class_type);
+
     self.begin_scope();
 
     {
       let mut scopes = self.scopes.borrow_mut();
       if let Some(back_scope) = scopes.last_mut() {
-        back_scope.insert("this".to_string(), true);
+        back_scope.insert("this".to_string(), Local { defined: true, slot: 0 });
       }
     }
 
@@ -242,6 +284,14 @@ impl super::stmt::Visitor<RloxType> for Resolver {
       }
     }
 
+    for accessor in stmt.getters.iter().chain(stmt.setters.iter()) {
+      if let Some(func_method) = accessor.borrow().as_any().downcast_ref::<Function<RloxType>>() {
+        self.resolve_function(func_method, FunctionType::Method)?;
+      } else {
+        return Err(RloxError::ResolverError("Expected method.".to_string()));
+      }
+    }
+
     self.end_scope();
 
     if let Some(_) = stmt.superclass {
@@ -262,8 +312,8 @@ impl super::expr::Visitor<RloxType> for Resolver {
       if !scopes.is_empty() {
         if let Some(back_scope) = scopes.last() {
           match back_scope.get(&expr.name.lexeme) {
-            Some(lexeme) => {
-              if *lexeme == false {
+            Some(local) => {
+              if !local.defined {
                 return Err(RloxError::ResolverError(format!("Can't read local variable in its own initializer - {}.", expr.name.lexeme)));
               }
             }
@@ -318,6 +368,28 @@ impl super::expr::Visitor<RloxType> for Resolver {
     Ok(RloxType::NullType)
   }
 
+  fn visit_interpolation_expr(&self, expr: &Interpolation<RloxType>) -> Result<RloxType, RloxError> {
+    for part in expr.parts.clone() {
+      self.resolve_expr(part)?;
+    }
+
+    Ok(RloxType::NullType)
+  }
+
+  fn visit_conditional_expr(&self, expr: &Conditional<RloxType>) -> Result<RloxType, RloxError> {
+    self.resolve_expr(expr.condition.clone())?;
+    self.resolve_expr(expr.then_branch.clone())?;
+    self.resolve_expr(expr.else_branch.clone())?;
+
+    Ok(RloxType::NullType)
+  }
+
+  fn visit_optional_chain_expr(&self, expr: &OptionalChain<RloxType>) -> Result<RloxType, RloxError> {
+    self.resolve_expr(expr.expression.clone())?;
+
+    Ok(RloxType::NullType)
+  }
+
   fn visit_unary_expr(&self, expr: &Unary<RloxType>) -> Result<RloxType, RloxError> {
     self.resolve_expr(expr.right.clone())?;
 
@@ -338,8 +410,10 @@ impl super::expr::Visitor<RloxType> for Resolver {
   }
 
   fn visit_this_expr(&self, expr: &This) -> Result<RloxType, RloxError> {
-    if *self.current_class.borrow() == ClassType::None {
-      return Err(RloxError::ResolverError("Can't use 'this' outside of a class".to_string()));
+    match *self.current_class.borrow() {
+      ClassType::None => return Err(RloxError::ResolverError("Can't use 'this' outside of a class".to_string())),
+      ClassType::Static => return Err(RloxError::ResolverError("Can't use 'this' in a class method or field.".to_string())),
+      _ => (),
     }
 
     self.resolve_local(VarExpr::ThisExpr(expr.clone()), expr.keyword.clone());
@@ -351,6 +425,8 @@ impl super::expr::Visitor<RloxType> for Resolver {
     let current_class = self.current_class.borrow();
     if *current_class == ClassType::None {
       return Err(RloxError::ResolverError("Can't use 'super' outside of a class.".to_string()));
+    } else if *current_class == ClassType::Static {
+      return Err(RloxError::ResolverError("Can't use 'super' in a class method or field.".to_string()));
     } else if *current_class != ClassType::SubClass {
       return Err(RloxError::ResolverError("Can't use 'super' in a class with no superclass.".to_string()));
     }
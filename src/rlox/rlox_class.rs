@@ -5,6 +5,8 @@ use super::{
   rlox_errors::RloxError,
   rlox_instance::RloxInstance,
   rlox_function::RloxFunction,
+  token::Token,
+  gc::{self, GcRef, Values},
 };
 use std::{
   cell::RefCell,
@@ -15,22 +17,83 @@ use std::{
 pub type RloxClassMethods = Rc<RefCell<HashMap<String, RloxFunction>>>;
 pub type RloxSuperClass = Rc<RefCell<Option<RloxClass>>>;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct RloxClass {
   name: String,
   methods: RloxClassMethods,
   superclass: RloxSuperClass,
+  class_methods: RloxClassMethods,
+  fields: Values,
+  getters: RloxClassMethods,
+  setters: RloxClassMethods,
 }
 
 impl RloxClass {
   pub fn new(name: &str, superclass: Option<RloxClass>, methods: RloxClassMethods) -> RloxClass {
+    let fields = Rc::new(RefCell::new(HashMap::new()));
+    gc::track_values(&fields);
     RloxClass {
       name: name.to_string(),
       methods,
       superclass: Rc::new(RefCell::new(superclass)),
+      class_methods: Rc::new(RefCell::new(HashMap::new())),
+      fields,
+      getters: Rc::new(RefCell::new(HashMap::new())),
+      setters: Rc::new(RefCell::new(HashMap::new())),
     }
   }
 
+  pub fn add_accessor(&self, name: &str, accessor: RloxFunction, is_setter: bool) {
+    let accessors = if is_setter { &self.setters } else { &self.getters };
+    accessors.borrow_mut().insert(name.to_string(), accessor);
+  }
+
+  // Getter or setter of the property, including the inherited ones
+  // unless a method of the same name overrides them.
+  pub fn find_accessor(&self, name: &str, is_setter: bool) -> Option<RloxFunction> {
+    let accessors = if is_setter { &self.setters } else { &self.getters };
+    if let Some(accessor) = accessors.borrow().get(name) {
+      return Some(accessor.clone());
+    }
+    if self.methods.borrow().contains_key(name) {
+      return None;
+    }
+    self.superclass.borrow().as_ref().and_then(|superklass| superklass.find_accessor(name, is_setter))
+  }
+
+  pub fn add_class_method(&self, name: &str, method: RloxFunction) {
+    self.class_methods.borrow_mut().insert(name.to_string(), method);
+  }
+
+  pub fn set_field(&self, name: &str, value: RloxType) {
+    self.fields.borrow_mut().insert(name.to_string(), value);
+  }
+
+  // Class fields and methods, including the inherited ones.
+  pub fn get(&self, name: &Token) -> Result<RloxType, RloxError> {
+    if let Some(value) = self.fields.borrow().get(&name.lexeme) {
+      return Ok(value.clone());
+    }
+    if let Some(method) = self.class_methods.borrow().get(&name.lexeme) {
+      return Ok(RloxType::CallableType(Box::new(method.clone())));
+    }
+
+    match self.superclass.borrow().clone() {
+      Some(superklass) => superklass.get(name).map_err(|_| self.undefined(name)),
+      None => Err(self.undefined(name)),
+    }
+  }
+
+  // Assigning an inherited field gives the class its own copy.
+  pub fn set(&self, name: &Token, value: &RloxType) -> Result<(), RloxError> {
+    self.set_field(&name.lexeme, value.clone());
+    Ok(())
+  }
+
+  fn undefined(&self, name: &Token) -> RloxError {
+    RloxError::InterpreterError(format!("Undefined property '{}' for class {}.", name.lexeme, self.name))
+  }
+
   pub fn class_name(&self) -> String {
     self.name.clone()
   }
@@ -72,7 +135,27 @@ impl Callable for RloxClass {
     self.class_name()
   }
 
+  fn inspect(&self) -> String {
+    format!("<class {}>", self.name)
+  }
+
   fn as_any(&self) -> &dyn std::any::Any {
     self
   }
+
+  fn trace(&self, refs: &mut Vec<GcRef>) {
+    refs.push(GcRef::Methods(self.methods.clone()));
+    refs.push(GcRef::Superclass(self.superclass.clone()));
+    refs.push(GcRef::Methods(self.class_methods.clone()));
+    refs.push(GcRef::Values(self.fields.clone()));
+    refs.push(GcRef::Methods(self.getters.clone()));
+    refs.push(GcRef::Methods(self.setters.clone()));
+  }
+}
+
+// A class is only equal to itself.
+impl PartialEq for RloxClass {
+  fn eq(&self, other: &Self) -> bool {
+    Rc::ptr_eq(&self.methods, &other.methods)
+  }
 }
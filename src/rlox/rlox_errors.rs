@@ -1,20 +1,35 @@
 use thiserror::Error;
-use super::rlox_type::RloxType;
+use super::{rlox_type::RloxType, rlox_function::RloxFunction};
 
 #[derive(Error, Debug)]
 pub enum RloxError {
-  #[error("Error parsing")]
+  #[error("{0}")]
+  ScannerError(String),
+
+  #[error("{0}")]
   ParserError(String),
 
-  #[error("Interpreter error")]
+  #[error("{0}")]
   InterpreterError(String),
 
-  #[error("Resolver error")]
+  #[error("{0}")]
   ResolverError(String),
 
   #[error("Return value.")]
   ReturnValue(RloxType),
 
+  #[error("Nil in optional chain.")]
+  NilChain,
+
+  #[error("Exit with code {0}.")]
+  Exit(i32),
+
+  #[error("Tail call.")]
+  TailCall(RloxFunction, Vec<RloxType>),
+
   #[error(transparent)]
   IOError(#[from] std::io::Error),
+
+  #[error(transparent)]
+  ReplError(#[from] rustyline::error::ReadlineError),
 }
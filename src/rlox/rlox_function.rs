@@ -8,6 +8,7 @@ use super::{
   environment::Environment,
   rlox_errors::RloxError,
   rlox_instance::RloxInstance,
+  gc::GcRef,
 };
 
 #[derive(Clone)]
@@ -39,10 +40,10 @@ impl RloxFunction {
       is_initializer: self.is_initializer,
     }
   }
-}
 
-impl Callable for RloxFunction {
-  fn call(&self, interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+  // Runs the body once, a call in tail position comes back as
+  // RloxError::TailCall for the caller to run.
+  fn call_body(&self, interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
     let closure = self.closure.borrow();
     let env = Environment::new_with_parent(closure.clone());
     for (i, param) in self.declaration.params.iter().enumerate() {
@@ -52,7 +53,7 @@ impl Callable for RloxFunction {
     match interpreter.execute_block(self.declaration.body.clone(), env) {
       Ok(r) => {
         if self.is_initializer {
-          return Ok(closure.get_at(0, "this")?);
+          return Ok(closure.get_at(0, 0)?);
         }
 
         Ok(r)
@@ -61,7 +62,7 @@ impl Callable for RloxFunction {
         match e {
           RloxError::ReturnValue(v) => {
             if self.is_initializer {
-              return closure.get_at(0, "this");
+              return closure.get_at(0, 0);
             }
             Ok(v)
           }
@@ -70,6 +71,24 @@ impl Callable for RloxFunction {
       }
     }
   }
+}
+
+impl Callable for RloxFunction {
+  fn call(&self, interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let mut function = self.clone();
+    let mut arguments = arguments;
+
+    // Tail calls reuse this frame, so deep recursion runs in constant stack.
+    loop {
+      match function.call_body(interpreter, arguments) {
+        Err(RloxError::TailCall(next, next_arguments)) => {
+          function = next;
+          arguments = next_arguments;
+        }
+        result => return result,
+      }
+    }
+  }
 
   fn arity(&self) -> usize {
     self.declaration.params.len()
@@ -79,9 +98,17 @@ impl Callable for RloxFunction {
     format!("<fn {} >", self.declaration.name.lexeme)
   }
 
+  fn inspect(&self) -> String {
+    format!("<fn {}/{}>", self.declaration.name.lexeme, self.arity())
+  }
+
   fn as_any(&self) -> &dyn std::any::Any {
     self
   }
+
+  fn trace(&self, refs: &mut Vec<GcRef>) {
+    refs.push(GcRef::Environment(self.closure.clone()));
+  }
 }
 
 impl std::fmt::Debug for RloxFunction {
@@ -90,8 +117,10 @@ impl std::fmt::Debug for RloxFunction {
   }
 }
 
+// The same declaration closing over the same environment, every
+// evaluation of a fun statement and every bound method is a new function.
 impl PartialEq for RloxFunction {
   fn eq(&self, other: &Self) -> bool {
-      self.name() == other.name()
+    Rc::ptr_eq(&self.declaration, &other.declaration) && Rc::ptr_eq(&self.closure, &other.closure)
   }
 }
@@ -5,12 +5,16 @@ use std::{
 };
 use super::{
   rlox_class::RloxClass,
+  rlox_function::RloxFunction,
   rlox_type::RloxType,
   rlox_errors::RloxError,
   token::Token,
+  callable::Callable,
+  interpreter::Interpreter,
+  gc::{self, GcRef, Trace},
 };
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct RloxInstance {
   klass: RloxClass,
   fields: Rc<RefCell<HashMap<String, RloxType>>>,
@@ -18,9 +22,11 @@ pub struct RloxInstance {
 
 impl RloxInstance {
   pub fn new(klass: RloxClass) -> RloxInstance {
+    let fields = Rc::new(RefCell::new(HashMap::new()));
+    gc::track_values(&fields);
     RloxInstance {
       klass,
-      fields: Rc::new(RefCell::new(HashMap::new())),
+      fields,
     }
   }
 
@@ -28,7 +34,27 @@ impl RloxInstance {
     format!("{} instance", self.klass.class_name())
   }
 
-  pub fn get(&self, name: &Token) -> Result<RloxType, RloxError> {
+  pub fn class_name(&self) -> String {
+    self.klass.class_name()
+  }
+
+  pub fn fields(&self) -> Vec<(String, RloxType)> {
+    let mut fields: Vec<(String, RloxType)> = self.fields.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    fields.sort_by(|a, b| a.0.cmp(&b.0));
+    fields
+  }
+
+  // Identifies the instance regardless of how many copies refer to it.
+  pub fn id(&self) -> usize {
+    Rc::as_ptr(&self.fields) as *const () as usize
+  }
+
+  // Getters take precedence over fields of the same name.
+  pub fn get(&self, interpreter: &Interpreter, name: &Token) -> Result<RloxType, RloxError> {
+    if let Some(getter) = self.klass.find_accessor(&name.lexeme, false) {
+      return getter.bind(self).call(interpreter, Vec::new());
+    }
+
     let fields = self.fields.borrow();
     if fields.contains_key(&name.lexeme) {
       return match fields.get(&name.lexeme) {
@@ -41,9 +67,40 @@ impl RloxInstance {
     Ok(RloxType::CallableType(Box::new(method.bind(self))))
   }
 
-  pub fn set(&self, name: &Token, value: &RloxType) -> Result<(), RloxError> {
-    let mut fields = self.fields.borrow_mut();
-    fields.insert(name.lexeme.clone(), value.clone());
+  // Method bound to the instance, None when its class doesn't define it.
+  pub fn method(&self, name: &str) -> Option<RloxFunction> {
+    self.klass.find_method(name).ok().map(|method| method.bind(self))
+  }
+
+  pub fn set(&self, interpreter: &Interpreter, name: &Token, value: &RloxType) -> Result<(), RloxError> {
+    if let Some(setter) = self.klass.find_accessor(&name.lexeme, true) {
+      setter.bind(self).call(interpreter, vec![value.clone()])?;
+      return Ok(());
+    }
+    if self.klass.find_accessor(&name.lexeme, false).is_some() {
+      return Err(RloxError::InterpreterError(format!("Property '{}' of {} has no setter.", name.lexeme, self.klass.class_name())));
+    }
+
+    self.set_field(&name.lexeme, value.clone());
     Ok(())
   }
+
+  pub fn set_field(&self, name: &str, value: RloxType) {
+    self.fields.borrow_mut().insert(name.to_string(), value);
+  }
+}
+
+// An instance is only equal to itself, comparing fields would recurse
+// through instances that refer to themselves.
+impl PartialEq for RloxInstance {
+  fn eq(&self, other: &Self) -> bool {
+    Rc::ptr_eq(&self.fields, &other.fields)
+  }
+}
+
+impl Trace for RloxInstance {
+  fn trace(&self, refs: &mut Vec<GcRef>) {
+    refs.push(GcRef::Values(self.fields.clone()));
+    self.klass.trace(refs);
+  }
 }
@@ -0,0 +1,138 @@
+use std::{
+  cell::RefCell,
+  collections::HashSet,
+  rc::Rc,
+};
+use super::{
+  callable::Callable,
+  interpreter::Interpreter,
+  rlox_type::RloxType,
+  rlox_errors::RloxError,
+  token::Token,
+  gc::{self, GcRef, Trace, Slots},
+};
+
+// Lists are shared, a copy of the value refers to the same items.
+#[derive(Clone, Debug)]
+pub struct RloxList {
+  items: Slots,
+}
+
+impl RloxList {
+  pub fn new(items: Vec<RloxType>) -> RloxList {
+    let items = Rc::new(RefCell::new(items));
+    gc::track_slots(&items);
+    RloxList { items }
+  }
+
+  pub fn items(&self) -> Vec<RloxType> {
+    self.items.borrow().clone()
+  }
+
+  pub fn id(&self) -> usize {
+    Rc::as_ptr(&self.items) as *const () as usize
+  }
+
+  pub fn get(&self, name: &Token) -> Result<RloxType, RloxError> {
+    let arity = match name.lexeme.as_str() {
+      "len" => 0,
+      "get" | "push" | "join" => 1,
+      "set" => 2,
+      _ => return Err(RloxError::InterpreterError(format!("Undefined property '{}' for list.", name.lexeme))),
+    };
+
+    Ok(RloxType::CallableType(Box::new(ListMethod {
+      list: self.clone(),
+      method: name.lexeme.clone(),
+      arity,
+    })))
+  }
+
+  fn index(&self, value: &RloxType) -> Result<usize, RloxError> {
+    let len = self.items.borrow().len();
+    match value {
+      RloxType::IntegerType(i) if *i >= 0 && (*i as usize) < len => Ok(*i as usize),
+      RloxType::IntegerType(i) => Err(RloxError::InterpreterError(format!("Index {} out of bounds for list of length {}.", i, len))),
+      _ => Err(RloxError::InterpreterError(format!("List index must be an integer, not {}.", value.type_name()))),
+    }
+  }
+}
+
+thread_local! {
+  // Pairs of lists being compared, a list that contains itself would
+  // otherwise recurse forever.
+  static COMPARING: RefCell<HashSet<(usize, usize)>> = RefCell::new(HashSet::new());
+}
+
+impl PartialEq for RloxList {
+  fn eq(&self, other: &Self) -> bool {
+    if Rc::ptr_eq(&self.items, &other.items) {
+      return true;
+    }
+
+    let pair = (self.id(), other.id());
+    if !COMPARING.with(|comparing| comparing.borrow_mut().insert(pair)) {
+      return true;
+    }
+    let equal = *self.items.borrow() == *other.items.borrow();
+    COMPARING.with(|comparing| comparing.borrow_mut().remove(&pair));
+    equal
+  }
+}
+
+impl Trace for RloxList {
+  fn trace(&self, refs: &mut Vec<GcRef>) {
+    refs.push(GcRef::Slots(self.items.clone()));
+  }
+}
+
+#[derive(Clone)]
+struct ListMethod {
+  list: RloxList,
+  method: String,
+  arity: usize,
+}
+
+impl Callable for ListMethod {
+  fn arity(&self) -> usize {
+    self.arity
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let list = &self.list;
+    match self.method.as_str() {
+      "len" => Ok(RloxType::IntegerType(list.items.borrow().len() as i64)),
+      "get" => {
+        let i = list.index(&arguments[0])?;
+        Ok(list.items.borrow()[i].clone())
+      }
+      "set" => {
+        let i = list.index(&arguments[0])?;
+        list.items.borrow_mut()[i] = arguments[1].clone();
+        Ok(arguments[1].clone())
+      }
+      "push" => {
+        list.items.borrow_mut().push(arguments[0].clone());
+        Ok(RloxType::NullType)
+      }
+      "join" => {
+        let separator = arguments[0].to_string();
+        let items: Vec<String> = list.items().iter().map(|item| item.to_string()).collect();
+        Ok(RloxType::StringType(items.join(&separator)))
+      }
+      _ => Err(RloxError::InterpreterError(format!("Undefined property '{}' for list.", self.method))),
+    }
+  }
+
+  fn name(&self) -> String {
+    format!("<native list method {}>", self.method)
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+
+  fn trace(&self, refs: &mut Vec<GcRef>) {
+    self.list.trace(refs);
+  }
+}
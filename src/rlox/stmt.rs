@@ -10,7 +10,9 @@ pub type Stm<T> = Rc<RefCell<dyn Stmt<T>>>;
 
 // program        → statement* EOF ;
 //
-// declaration    → funDecl
+// declaration    → classDecl
+//                | funDecl
+//                | testDecl
 //                | varDecl
 //                | statement ;
 //
@@ -34,19 +36,24 @@ pub type Stm<T> = Rc<RefCell<dyn Stmt<T>>>;
 //                ( "else" statement )? ;
 // block          → "{" declaration* "}" ;
 // classDecl      → "class" IDENTIFIER ( "<" IDENTIFIER )?
-//                  "{" function* "}" ;
+//                  "{" ( function | classMember | getter | setter )* "}" ;
+// classMember    → "class" ( function | IDENTIFIER ( "=" expression )? ";" ) ;
+// getter         → IDENTIFIER block ;
+// setter         → "set" IDENTIFIER "(" IDENTIFIER ")" block ;
+// testDecl       → "test" STRING block ;
 // exprStmt       → expression ";" ;
 // printStmt      → "print" expression ";" ;
 
 generate_ast! {
   Stmt {
     visit_block_stmt Block T => statements: Vec<Stm<T>>;
-    visit_class_stmt Class T => name: Token, superclass: Option<Variable>, methods: Vec<Stm<T>>;
+    visit_class_stmt Class T => name: Token, superclass: Option<Variable>, methods: Vec<Stm<T>>, class_methods: Vec<Stm<T>>, fields: Vec<Stm<T>>, getters: Vec<Stm<T>>, setters: Vec<Stm<T>>;
     visit_expression_stmt Expression T => expression: Exp<T>;
     visit_function_stmt Function T => name: Token, params: Vec<Token>, body: Vec<Stm<T>>;
     visit_if_stmt If T => condition: Exp<T>, then_branch: Stm<T>, else_branch: Option<Stm<T>>;
     visit_print_stmt Print T => expression: Exp<T>;
     visit_return_stmt Return T => keyword: Token, value: Exp<T>;
+    visit_test_stmt Test T => name: Token, body: Vec<Stm<T>>;
     visit_var_stmt Var T => name: Token, initializer: Exp<T>;
     visit_while_stmt While T => condition: Exp<T>, body: Stm<T>;
   }
@@ -0,0 +1,181 @@
+// Methods of string values, "text".upper() and the like.
+//
+// Strings are indexed by unicode code points, so "héllo".len() is 5 and
+// substring() never splits a character.
+use super::{
+  callable::Callable,
+  interpreter::Interpreter,
+  rlox_type::RloxType,
+  rlox_errors::RloxError,
+  rlox_list::RloxList,
+  token::Token,
+};
+
+const METHODS: &[(&str, usize)] = &[
+  ("len", 0),
+  ("upper", 0),
+  ("lower", 0),
+  ("trim", 0),
+  ("split", 1),
+  ("replace", 2),
+  ("find", 1),
+  ("startsWith", 1),
+  ("endsWith", 1),
+  ("substring", 2),
+  ("repeat", 1),
+  ("chars", 0),
+  ("codePoints", 0),
+];
+
+// Largest string repeat() builds, in bytes.
+const MAX_REPEAT_LEN: usize = 1 << 30;
+
+pub fn get(receiver: &str, name: &Token) -> Result<RloxType, RloxError> {
+  match METHODS.iter().find(|(method, _)| *method == name.lexeme) {
+    Some((method, arity)) => Ok(RloxType::CallableType(Box::new(StringMethod {
+      receiver: receiver.to_string(),
+      method,
+      arity: *arity,
+    }))),
+    None => Err(RloxError::InterpreterError(format!("Undefined property '{}' for string.", name.lexeme))),
+  }
+}
+
+#[derive(Clone)]
+struct StringMethod {
+  receiver: String,
+  method: &'static str,
+  arity: usize,
+}
+
+impl StringMethod {
+  fn string_argument<'a>(&self, value: &'a RloxType) -> Result<&'a str, RloxError> {
+    match value {
+      RloxType::StringType(s) => Ok(s),
+      _ => Err(RloxError::InterpreterError(format!("{}() expects a string argument, not {}.", self.method, value.type_name()))),
+    }
+  }
+
+  fn count_argument(&self, value: &RloxType) -> Result<usize, RloxError> {
+    match value {
+      RloxType::IntegerType(i) if *i >= 0 => Ok(*i as usize),
+      _ => Err(RloxError::InterpreterError(format!("{}() expects a non-negative integer argument, not {}.", self.method, value.inspect()))),
+    }
+  }
+}
+
+impl Callable for StringMethod {
+  fn arity(&self) -> usize {
+    self.arity
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let s = self.receiver.as_str();
+    let string = |s: String| Ok(RloxType::StringType(s));
+    let list = |items: Vec<RloxType>| Ok(RloxType::ListType(RloxList::new(items)));
+
+    match self.method {
+      "len" => Ok(RloxType::IntegerType(s.chars().count() as i64)),
+      "upper" => string(s.to_uppercase()),
+      "lower" => string(s.to_lowercase()),
+      "trim" => string(s.trim().to_string()),
+      "split" => {
+        let separator = self.string_argument(&arguments[0])?;
+        // An empty separator splits into characters.
+        if separator.is_empty() {
+          return list(s.chars().map(|c| RloxType::StringType(c.to_string())).collect());
+        }
+        list(s.split(separator).map(|part| RloxType::StringType(part.to_string())).collect())
+      }
+      "replace" => string(s.replace(self.string_argument(&arguments[0])?, self.string_argument(&arguments[1])?)),
+      "find" => {
+        let needle = self.string_argument(&arguments[0])?;
+        let index = s.find(needle).map(|byte| s[..byte].chars().count() as i64).unwrap_or(-1);
+        Ok(RloxType::IntegerType(index))
+      }
+      "startsWith" => Ok(RloxType::BooleanType(s.starts_with(self.string_argument(&arguments[0])?))),
+      "endsWith" => Ok(RloxType::BooleanType(s.ends_with(self.string_argument(&arguments[0])?))),
+      "substring" => {
+        let start = self.count_argument(&arguments[0])?;
+        let end = self.count_argument(&arguments[1])?;
+        let len = s.chars().count();
+        if start > end || end > len {
+          return Err(RloxError::InterpreterError(format!("Substring range {}..{} out of bounds for string of length {}.", start, end, len)));
+        }
+        string(s.chars().skip(start).take(end - start).collect())
+      }
+      "repeat" => {
+        let count = self.count_argument(&arguments[0])?;
+        match s.len().checked_mul(count) {
+          Some(len) if len <= MAX_REPEAT_LEN => string(s.repeat(count)),
+          _ => Err(RloxError::InterpreterError(format!("repeat() result would be too long, {} copies of a string of length {}.", count, s.chars().count()))),
+        }
+      }
+      "chars" => list(s.chars().map(|c| RloxType::StringType(c.to_string())).collect()),
+      "codePoints" => list(s.chars().map(|c| RloxType::IntegerType(c as i64)).collect()),
+      _ => Err(RloxError::InterpreterError(format!("Undefined property '{}' for string.", self.method))),
+    }
+  }
+
+  fn name(&self) -> String {
+    format!("<native string method {}>", self.method)
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::rlox::TokenType;
+
+  fn call(receiver: &str, method: &str, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let name = Token::new(TokenType::IDENTIFIER, method.to_string(), None, 1);
+    match get(receiver, &name)? {
+      RloxType::CallableType(c) => c.call(&Interpreter::new(), arguments),
+      _ => unreachable!(),
+    }
+  }
+
+  fn s(value: &str) -> RloxType {
+    RloxType::StringType(value.to_string())
+  }
+
+  #[test]
+  fn test_unicode_indexing() -> Result<(), RloxError> {
+    assert_eq!(call("héllo wörld", "len", vec![])?, RloxType::IntegerType(11));
+    assert_eq!(call("héllo wörld", "find", vec![s("wö")])?, RloxType::IntegerType(6));
+    assert_eq!(call("héllo wörld", "find", vec![s("x")])?, RloxType::IntegerType(-1));
+    assert_eq!(call("héllo wörld", "substring", vec![RloxType::IntegerType(1), RloxType::IntegerType(4)])?, s("éll"));
+    assert_eq!(call("😀a", "codePoints", vec![])?.to_string(), "[128512, 97]");
+    assert_eq!(call("😀a", "chars", vec![])?.inspect(), "[\"😀\", \"a\"]");
+    Ok(())
+  }
+
+  #[test]
+  fn test_methods() -> Result<(), RloxError> {
+    assert_eq!(call("Straße", "upper", vec![])?, s("STRASSE"));
+    assert_eq!(call("ABC", "lower", vec![])?, s("abc"));
+    assert_eq!(call("  x  ", "trim", vec![])?, s("x"));
+    assert_eq!(call("a,b,,c", "split", vec![s(",")])?.inspect(), "[\"a\", \"b\", \"\", \"c\"]");
+    assert_eq!(call("abc", "split", vec![s("")])?.inspect(), "[\"a\", \"b\", \"c\"]");
+    assert_eq!(call("aXbX", "replace", vec![s("X"), s("-")])?, s("a-b-"));
+    assert_eq!(call("prefix", "startsWith", vec![s("pre")])?, RloxType::BooleanType(true));
+    assert_eq!(call("suffix", "endsWith", vec![s("pre")])?, RloxType::BooleanType(false));
+    assert_eq!(call("ab", "repeat", vec![RloxType::IntegerType(3)])?, s("ababab"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_errors() {
+    let range = call("abc", "substring", vec![RloxType::IntegerType(2), RloxType::IntegerType(5)]);
+    assert_eq!(range.unwrap_err().to_string(), "Substring range 2..5 out of bounds for string of length 3.");
+    assert_eq!(call("abc", "repeat", vec![RloxType::IntegerType(-1)]).unwrap_err().to_string(), "repeat() expects a non-negative integer argument, not -1.");
+    assert_eq!(call("ab", "repeat", vec![RloxType::IntegerType(i64::MAX)]).unwrap_err().to_string(), "repeat() result would be too long, 9223372036854775807 copies of a string of length 2.");
+    assert_eq!(call("", "repeat", vec![RloxType::IntegerType(i64::MAX)]).unwrap().to_string(), "");
+    assert_eq!(call("abc", "split", vec![RloxType::IntegerType(1)]).unwrap_err().to_string(), "split() expects a string argument, not integer.");
+    assert_eq!(call("abc", "nope", vec![]).unwrap_err().to_string(), "Undefined property 'nope' for string.");
+  }
+}
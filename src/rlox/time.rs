@@ -0,0 +1,416 @@
+// The time namespace.
+//
+// time.millis() and time.nanos() read a monotonic clock for measuring,
+// time.now(), time.date(), time.datetime(), time.fromTimestamp() and
+// time.parse() create DateTime objects and time.duration() creates
+// Durations. DateTimes carry a fixed UTC offset, inZone() converts between
+// the offsets in ZONES, daylight saving time isn't applied.
+use std::{
+  fmt,
+  sync::OnceLock,
+  time::Instant,
+};
+use chrono::{
+  format::{Item, StrftimeItems},
+  DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Timelike, Utc,
+};
+use super::{
+  callable::Callable,
+  interpreter::Interpreter,
+  native_object::NativeObject,
+  rlox_type::RloxType,
+  rlox_errors::RloxError,
+  numeric,
+};
+
+// Offsets from UTC in minutes.
+const ZONES: &[(&str, i32)] = &[
+  ("UTC", 0),
+  ("GMT", 0),
+  ("WET", 0),
+  ("CET", 60),
+  ("EET", 120),
+  ("MSK", 180),
+  ("IST", 330),
+  ("CST", -360),
+  ("EST", -300),
+  ("MST", -420),
+  ("PST", -480),
+  ("AKST", -540),
+  ("HST", -600),
+  ("EDT", -240),
+  ("CDT", -300),
+  ("MDT", -360),
+  ("PDT", -420),
+  ("WEST", 60),
+  ("CEST", 120),
+  ("EEST", 180),
+  ("JST", 540),
+  ("KST", 540),
+  ("HKT", 480),
+  ("SGT", 480),
+  ("AEST", 600),
+  ("AEDT", 660),
+  ("NZST", 720),
+  ("NZDT", 780),
+];
+
+const FUNCTIONS: &[(&str, usize)] = &[
+  ("millis", 0),
+  ("nanos", 0),
+  ("now", 0),
+  ("date", 3),
+  ("datetime", 6),
+  ("fromTimestamp", 1),
+  ("parse", 2),
+  ("duration", 1),
+];
+
+static START: OnceLock<Instant> = OnceLock::new();
+
+pub fn namespace() -> RloxType {
+  START.get_or_init(Instant::now);
+
+  let members = FUNCTIONS.iter()
+    .map(|(name, arity)| (*name, RloxType::CallableType(Box::new(TimeFunction { name, arity: *arity }))))
+    .collect();
+
+  RloxType::NativeType(NativeObject::new("time", members))
+}
+
+fn error(message: String) -> RloxError {
+  RloxError::InterpreterError(message)
+}
+
+fn integer_argument(name: &str, value: &RloxType) -> Result<i64, RloxError> {
+  match value {
+    RloxType::IntegerType(i) => Ok(*i),
+    _ => Err(error(format!("{}() expects an integer, not {}.", name, value.type_name()))),
+  }
+}
+
+fn string_argument<'a>(name: &str, value: &'a RloxType) -> Result<&'a str, RloxError> {
+  match value {
+    RloxType::StringType(s) => Ok(s),
+    _ => Err(error(format!("{}() expects a string, not {}.", name, value.type_name()))),
+  }
+}
+
+fn date_argument(name: &str, value: &RloxType) -> Result<DateTime<FixedOffset>, RloxError> {
+  match value {
+    RloxType::NativeType(native) if native.value::<Date>().is_some() => Ok(native.value::<Date>().unwrap().0),
+    _ => Err(error(format!("{}() expects a DateTime, not {}.", name, value.type_name()))),
+  }
+}
+
+fn duration_argument(name: &str, value: &RloxType) -> Result<Duration, RloxError> {
+  match value {
+    RloxType::NativeType(native) if native.value::<Span>().is_some() => Ok(native.value::<Span>().unwrap().0),
+    _ => Err(error(format!("{}() expects a Duration, not {}.", name, value.type_name()))),
+  }
+}
+
+// "UTC", "PST" or an explicit offset like "+05:30".
+fn zone(name: &str) -> Option<FixedOffset> {
+  if let Some((_, minutes)) = ZONES.iter().find(|(zone, _)| zone.eq_ignore_ascii_case(name)) {
+    return FixedOffset::east_opt(minutes * 60);
+  }
+
+  let sign = match name.chars().next()? {
+    '+' => 1,
+    '-' => -1,
+    _ => return None,
+  };
+  let (hours, minutes) = name[1..].split_once(':')?;
+  if hours.len() != 2 || minutes.len() != 2 {
+    return None;
+  }
+  let hours: i32 = hours.parse().ok()?;
+  let minutes: i32 = minutes.parse().ok()?;
+  if minutes >= 60 {
+    return None;
+  }
+  FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+fn check_format(format: &str) -> Result<(), RloxError> {
+  if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+    return Err(error(format!("Invalid time format '{}'.", format)));
+  }
+  Ok(())
+}
+
+// Tries a format with an offset first, then assumes UTC.
+fn parse(text: &str, format: &str) -> Option<DateTime<FixedOffset>> {
+  let utc = FixedOffset::east_opt(0)?;
+
+  DateTime::parse_from_str(text, format).ok()
+    .or_else(|| NaiveDateTime::parse_from_str(text, format).ok().and_then(|dt| utc.from_local_datetime(&dt).single()))
+    .or_else(|| {
+      NaiveDate::parse_from_str(text, format).ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .and_then(|dt| utc.from_local_datetime(&dt).single())
+    })
+}
+
+struct Date(DateTime<FixedOffset>);
+
+impl fmt::Display for Date {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+  }
+}
+
+struct Span(Duration);
+
+impl fmt::Display for Span {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+fn date_object(date: DateTime<FixedOffset>) -> RloxType {
+  let method = |name: &'static str, arity: usize| {
+    (name, RloxType::CallableType(Box::new(DateMethod { date, name, arity })))
+  };
+
+  RloxType::NativeType(NativeObject::new("DateTime", vec![
+    method("year", 0),
+    method("month", 0),
+    method("day", 0),
+    method("hour", 0),
+    method("minute", 0),
+    method("second", 0),
+    method("weekday", 0),
+    method("timestamp", 0),
+    method("offset", 0),
+    method("format", 1),
+    method("add", 1),
+    method("since", 1),
+    method("inZone", 1),
+  ]).with_value(Date(date)))
+}
+
+fn duration_object(duration: Duration) -> RloxType {
+  let method = |name: &'static str, arity: usize| {
+    (name, RloxType::CallableType(Box::new(DurationMethod { duration, name, arity })))
+  };
+
+  RloxType::NativeType(NativeObject::new("Duration", vec![
+    method("seconds", 0),
+    method("millis", 0),
+    method("add", 1),
+  ]).with_value(Span(duration)))
+}
+
+#[derive(Clone)]
+struct TimeFunction {
+  name: &'static str,
+  arity: usize,
+}
+
+impl Callable for TimeFunction {
+  fn arity(&self) -> usize {
+    self.arity
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let elapsed = || START.get_or_init(Instant::now).elapsed();
+    let integer = |i: usize| integer_argument(&format!("time.{}", self.name), &arguments[i]);
+    let invalid = || error(format!("time.{}() got an invalid date.", self.name));
+    let utc = FixedOffset::east_opt(0).unwrap();
+
+    match self.name {
+      "millis" => Ok(RloxType::NumberType(elapsed().as_secs_f64() * 1000.0)),
+      "nanos" => Ok(RloxType::IntegerType(elapsed().as_nanos() as i64)),
+      "now" => Ok(date_object(Utc::now().with_timezone(&utc))),
+      "date" | "datetime" => {
+        let mut parts = vec![];
+        for i in 0..6 {
+          parts.push(if i < arguments.len() { integer(i)? } else { 0 });
+        }
+        if parts[0].abs() > 200_000 || parts[1..].iter().any(|part| !(0..=60).contains(part)) {
+          return Err(invalid());
+        }
+        let date = NaiveDate::from_ymd_opt(parts[0] as i32, parts[1] as u32, parts[2] as u32)
+          .and_then(|d| d.and_hms_opt(parts[3] as u32, parts[4] as u32, parts[5] as u32))
+          .ok_or_else(invalid)?;
+        utc.from_local_datetime(&date).single().map(date_object).ok_or_else(invalid)
+      }
+      "fromTimestamp" => {
+        let seconds = numeric::to_float(&arguments[0])
+          .ok_or_else(|| error(format!("time.fromTimestamp() expects a number, not {}.", arguments[0].type_name())))?;
+        let nanos = (seconds * 1e9).round();
+        if !nanos.is_finite() || nanos.abs() >= i64::MAX as f64 {
+          return Err(invalid());
+        }
+        Ok(date_object(utc.timestamp_nanos(nanos as i64)))
+      }
+      "parse" => {
+        let text = string_argument("time.parse", &arguments[0])?;
+        let format = string_argument("time.parse", &arguments[1])?;
+        check_format(format)?;
+        parse(text, format)
+          .map(date_object)
+          .ok_or_else(|| error(format!("Can't parse '{}' with format '{}'.", text, format)))
+      }
+      "duration" => {
+        let seconds = numeric::to_float(&arguments[0])
+          .ok_or_else(|| error(format!("time.duration() expects a number, not {}.", arguments[0].type_name())))?;
+        let nanos = (seconds * 1e9).round();
+        if !nanos.is_finite() || nanos.abs() >= i64::MAX as f64 {
+          return Err(error("Duration out of range.".to_string()));
+        }
+        Ok(duration_object(Duration::nanoseconds(nanos as i64)))
+      }
+      _ => Err(error(format!("Undefined property '{}' for time.", self.name))),
+    }
+  }
+
+  fn name(&self) -> String {
+    format!("<native time.{} function>", self.name)
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+struct DateMethod {
+  date: DateTime<FixedOffset>,
+  name: &'static str,
+  arity: usize,
+}
+
+impl Callable for DateMethod {
+  fn arity(&self) -> usize {
+    self.arity
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let integer = |n: u32| Ok(RloxType::IntegerType(n as i64));
+
+    match self.name {
+      "year" => Ok(RloxType::IntegerType(self.date.year() as i64)),
+      "month" => integer(self.date.month()),
+      "day" => integer(self.date.day()),
+      "hour" => integer(self.date.hour()),
+      "minute" => integer(self.date.minute()),
+      "second" => integer(self.date.second()),
+      "weekday" => Ok(RloxType::StringType(self.date.format("%A").to_string())),
+      "timestamp" => Ok(RloxType::IntegerType(self.date.timestamp())),
+      "offset" => Ok(RloxType::StringType(self.date.format("%:z").to_string())),
+      "format" => {
+        let format = string_argument("format", &arguments[0])?;
+        check_format(format)?;
+        Ok(RloxType::StringType(self.date.format(format).to_string()))
+      }
+      "add" => {
+        let duration = duration_argument("add", &arguments[0])?;
+        self.date.checked_add_signed(duration)
+          .map(date_object)
+          .ok_or_else(|| error("Date out of range.".to_string()))
+      }
+      "since" => {
+        let other = date_argument("since", &arguments[0])?;
+        Ok(duration_object(self.date.signed_duration_since(other)))
+      }
+      "inZone" => {
+        let name = string_argument("inZone", &arguments[0])?;
+        zone(name)
+          .map(|offset| date_object(self.date.with_timezone(&offset)))
+          .ok_or_else(|| error(format!("Unknown time zone '{}'.", name)))
+      }
+      _ => Err(error(format!("Undefined property '{}' for DateTime.", self.name))),
+    }
+  }
+
+  fn name(&self) -> String {
+    format!("<native DateTime.{} method>", self.name)
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[derive(Clone)]
+struct DurationMethod {
+  duration: Duration,
+  name: &'static str,
+  arity: usize,
+}
+
+impl Callable for DurationMethod {
+  fn arity(&self) -> usize {
+    self.arity
+  }
+
+  fn call(&self, _interpreter: &Interpreter, arguments: Vec<RloxType>) -> Result<RloxType, RloxError> {
+    let nanos = || self.duration.num_nanoseconds().map(|n| n as f64).unwrap_or(self.duration.num_milliseconds() as f64 * 1e6);
+
+    match self.name {
+      "seconds" => Ok(RloxType::NumberType(nanos() / 1e9)),
+      "millis" => Ok(RloxType::NumberType(nanos() / 1e6)),
+      "add" => {
+        let other = duration_argument("add", &arguments[0])?;
+        self.duration.checked_add(&other)
+          .map(duration_object)
+          .ok_or_else(|| error("Duration out of range.".to_string()))
+      }
+      _ => Err(error(format!("Undefined property '{}' for Duration.", self.name))),
+    }
+  }
+
+  fn name(&self) -> String {
+    format!("<native Duration.{} method>", self.name)
+  }
+
+  fn as_any(&self) -> &dyn std::any::Any {
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_zone() {
+    assert_eq!(zone("UTC"), FixedOffset::east_opt(0));
+    assert_eq!(zone("pst"), FixedOffset::east_opt(-8 * 3600));
+    assert_eq!(zone("+05:30"), FixedOffset::east_opt(5 * 3600 + 30 * 60));
+    assert_eq!(zone("-03:00"), FixedOffset::east_opt(-3 * 3600));
+    assert_eq!(zone("Mars"), None);
+    assert_eq!(zone("+5:30"), None);
+    assert_eq!(zone("+05:75"), None);
+  }
+
+  #[test]
+  fn test_parse() {
+    let date = parse("2024-02-29 13:45:00", "%Y-%m-%d %H:%M:%S").unwrap();
+    assert_eq!(Date(date).to_string(), "2024-02-29T13:45:00Z");
+
+    let date = parse("2024-02-29", "%Y-%m-%d").unwrap();
+    assert_eq!(Date(date).to_string(), "2024-02-29T00:00:00Z");
+
+    let date = parse("2024-02-29 13:45 +0100", "%Y-%m-%d %H:%M %z").unwrap();
+    assert_eq!(Date(date).to_string(), "2024-02-29T13:45:00+01:00");
+
+    assert!(parse("2023-02-29", "%Y-%m-%d").is_none());
+    assert!(check_format("%Y-%Q").is_err());
+  }
+
+  #[test]
+  fn test_monotonic_clock() -> Result<(), RloxError> {
+    let interpreter = Interpreter::new();
+    let nanos = TimeFunction { name: "nanos", arity: 0 };
+    let (first, second) = (nanos.call(&interpreter, vec![])?, nanos.call(&interpreter, vec![])?);
+    match (first, second) {
+      (RloxType::IntegerType(a), RloxType::IntegerType(b)) => assert!(a <= b),
+      values => panic!("unexpected {:?}", values),
+    }
+    Ok(())
+  }
+}
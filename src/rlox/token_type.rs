@@ -15,6 +15,13 @@ enum_to_str! {
     SEMICOLON,
     SLASH,
     STAR,
+    PERCENT,
+    AMPERSAND,
+    PIPE,
+    CARET,
+    TILDE,
+    QUESTION,
+    COLON,
 
     // One or two character tokens.
     BANG,
@@ -25,15 +32,29 @@ enum_to_str! {
     GREATEREQUAL,
     LESS,
     LESSEQUAL,
+    PLUSEQUAL,
+    MINUSEQUAL,
+    STAREQUAL,
+    SLASHEQUAL,
+    PERCENTEQUAL,
+    PLUSPLUS,
+    MINUSMINUS,
+    QUESTIONQUESTION,
+    QUESTIONDOT,
+    STARSTAR,
+    LESSLESS,
+    GREATERGREATER,
 
     // Literals.
     IDENTIFIER,
     STRING,
+    INTERPOLATION,
     NUMBER,
 
     // Keywords.
     AND,
     CLASS,
+    DIV,
     ELSE,
     FALSE,
     FUN,
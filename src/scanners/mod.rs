@@ -1,5 +1,7 @@
+mod number_literal;
 mod scanner;
 mod source_loader;
+mod string_literal;
 
 pub use self::scanner::*;
 pub use self::source_loader::*;
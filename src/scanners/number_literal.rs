@@ -0,0 +1,149 @@
+use crate::rlox::Literal;
+use num_bigint::BigInt;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+// Parses the text of a number literal:
+//
+//   42  1_000_000  0xFF  0b1010  0o755   integers
+//   4.2  1e-9  6.02E23                    floats
+//   123n  0xFFn                           bigints
+//   19.99m  5m                            decimals
+//
+// Errors are messages for the scanner diagnostics.
+pub fn parse_number_literal(text: &str) -> Result<Literal, String> {
+  let invalid = || format!("Invalid number literal '{}'.", text);
+
+  let (body, suffix) = match text.chars().last() {
+    Some(c @ 'n') | Some(c @ 'm') => (&text[..text.len() - 1], Some(c)),
+    _ => (text, None),
+  };
+
+  let radix = match body.get(..2) {
+    Some("0x") | Some("0X") => 16,
+    Some("0b") | Some("0B") => 2,
+    Some("0o") | Some("0O") => 8,
+    _ => 10,
+  };
+
+  if radix != 10 {
+    let digits = &body[2..];
+    if digits.is_empty() || !valid_separators(digits, radix) || !digits.chars().all(|c| c == '_' || c.is_digit(radix)) {
+      return Err(invalid());
+    }
+
+    let digits = digits.replace('_', "");
+    return match suffix {
+      Some('n') => BigInt::parse_bytes(digits.as_bytes(), radix).map(Literal::BigIntType).ok_or_else(invalid),
+      Some(_) => Err(invalid()),
+      None => i64::from_str_radix(&digits, radix).map(Literal::IntegerType).map_err(|_| too_large(text)),
+    };
+  }
+
+  if !valid_decimal(body) || !valid_separators(body, 10) {
+    return Err(invalid());
+  }
+
+  let number = body.replace('_', "");
+  let is_float = number.contains(['.', 'e', 'E']);
+  match suffix {
+    Some('n') if is_float => Err(format!("Bigint literal '{}' can't have a fraction or exponent.", text)),
+    Some('n') => BigInt::from_str(&number).map(Literal::BigIntType).map_err(|_| invalid()),
+    Some(_) => Decimal::from_str(&number)
+      .or_else(|_| Decimal::from_scientific(&number))
+      .map(Literal::DecimalType)
+      .map_err(|_| invalid()),
+    None if is_float => number.parse::<f64>().map(Literal::NumberType).map_err(|_| invalid()),
+    None => number.parse::<i64>().map(Literal::IntegerType).map_err(|_| too_large(text)),
+  }
+}
+
+fn too_large(text: &str) -> String {
+  format!("Integer literal '{}' is too large, use the n suffix for bigints.", text)
+}
+
+// digits ( "." digits )? ( ( "e" | "E" ) ( "+" | "-" )? digits )?
+fn valid_decimal(text: &str) -> bool {
+  let (mantissa, exponent) = match text.find(['e', 'E']) {
+    Some(i) => (&text[..i], Some(&text[i + 1..])),
+    None => (text, None),
+  };
+
+  let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit() || c == '_');
+
+  let mantissa_ok = match mantissa.find('.') {
+    Some(i) => is_digits(&mantissa[..i]) && is_digits(&mantissa[i + 1..]),
+    None => is_digits(mantissa),
+  };
+
+  let exponent_ok = match exponent {
+    Some(e) => is_digits(e.strip_prefix(['+', '-']).unwrap_or(e)),
+    None => true,
+  };
+
+  mantissa_ok && exponent_ok
+}
+
+// Separators only go between two digits.
+fn valid_separators(text: &str, radix: u32) -> bool {
+  let chars: Vec<char> = text.chars().collect();
+  chars.iter().enumerate().all(|(i, c)| {
+    *c != '_' || (i > 0 && chars[i - 1].is_digit(radix) && chars.get(i + 1).is_some_and(|n| n.is_digit(radix)))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_valid_literals() {
+    let test_input = [
+      ("42", "42"),
+      ("1_000_000", "1000000"),
+      ("0xFF", "255"),
+      ("0b1010", "10"),
+      ("0o755", "493"),
+      ("1e-9", "0.000000001"),
+      ("6.02E23", "602000000000000000000000"),
+      ("1e3", "1000"),
+      ("0.5", "0.5"),
+      ("0xFFFF_FFFF_FFFF_FFFF_FFn", "4722366482869645213695n"),
+      ("1_000n", "1000n"),
+      ("19.99m", "19.99m"),
+      ("1.5e2m", "150m"),
+    ];
+
+    for (input, expected) in test_input.iter() {
+      assert_eq!(parse_number_literal(input).map(|l| l.inspect()), Ok(expected.to_string()), "{}", input);
+    }
+  }
+
+  #[test]
+  fn test_exponent_makes_float() {
+    assert!(matches!(parse_number_literal("1e3"), Ok(Literal::NumberType(n)) if n == 1000.0));
+    assert!(matches!(parse_number_literal("0xFF"), Ok(Literal::IntegerType(255))));
+  }
+
+  #[test]
+  fn test_malformed_literals() {
+    let test_input = [
+      ("0x", "Invalid number literal '0x'."),
+      ("0xZZ", "Invalid number literal '0xZZ'."),
+      ("0b102", "Invalid number literal '0b102'."),
+      ("1e", "Invalid number literal '1e'."),
+      ("1e+", "Invalid number literal '1e+'."),
+      ("1__0", "Invalid number literal '1__0'."),
+      ("1_", "Invalid number literal '1_'."),
+      ("1_.5", "Invalid number literal '1_.5'."),
+      ("12abc", "Invalid number literal '12abc'."),
+      ("0xFFm", "Invalid number literal '0xFFm'."),
+      ("1.5n", "Bigint literal '1.5n' can't have a fraction or exponent."),
+      ("9223372036854775808", "Integer literal '9223372036854775808' is too large, use the n suffix for bigints."),
+    ];
+
+    for (input, expected) in test_input.iter() {
+      assert_eq!(parse_number_literal(input).map(|l| l.inspect()), Err(expected.to_string()), "{}", input);
+    }
+  }
+}
@@ -1,7 +1,10 @@
-use crate::rlox::report;
+use crate::rlox::error_message;
 use crate::rlox::Literal;
+use crate::rlox::RloxError;
 use crate::rlox::Token;
 use crate::rlox::TokenType;
+use super::number_literal::parse_number_literal;
+use super::string_literal::{closing_brace, split_interpolations, strip_indentation, strip_segments_indentation, unescape, Segment};
 use std::collections::HashMap;
 
 type KeywordsType = HashMap<String, TokenType>;
@@ -13,6 +16,7 @@ pub struct Scanner {
   current: usize,
   line: usize,
   keywords: KeywordsType,
+  diagnostics: Vec<String>,
 }
 
 impl Scanner {
@@ -24,6 +28,7 @@ impl Scanner {
       current: 0,
       line: 1,
       keywords: Scanner::initialize_keywords(),
+      diagnostics: Vec::new(),
     }
   }
 
@@ -31,6 +36,7 @@ impl Scanner {
     let mut keywords = HashMap::<String, TokenType>::new();
     keywords.insert(String::from("and"), TokenType::AND);
     keywords.insert(String::from("class"), TokenType::CLASS);
+    keywords.insert(String::from("div"), TokenType::DIV);
     keywords.insert(String::from("else"), TokenType::ELSE);
     keywords.insert(String::from("false"), TokenType::FALSE);
     keywords.insert(String::from("for"), TokenType::FOR);
@@ -57,6 +63,10 @@ impl Scanner {
     self.source[self.current - 1]
   }
 
+  fn error(&mut self, message: &str) {
+    self.diagnostics.push(error_message(self.line, message));
+  }
+
   fn add_token(&mut self, token_type: TokenType) {
     self.add_token_with_literal(token_type, None);
   }
@@ -89,27 +99,83 @@ impl Scanner {
     self.source[self.current]
   }
 
-  fn process_string_literal(&mut self) {
-    while self.peek() != '"' && !self.is_at_end() {
-      if self.peek() == '\n' {
+  // Called after the opening ", raw strings don't process escapes.
+  fn process_string_literal(&mut self, raw: bool) {
+    let multiline = self.peek() == '"' && self.peek_next() == '"';
+    if multiline {
+      self.advance();
+      self.advance();
+    }
+
+    let content_start = self.current;
+    let first_line = self.line;
+    loop {
+      if self.is_at_end() {
+        self.error("Unterminated string.");
+        return;
+      }
+
+      if self.peek() == '"' && (!multiline || self.is_closing_triple_quote()) {
+        break;
+      }
+
+      let c = self.advance();
+      if c == '\\' && !raw && !self.is_at_end() {
+        self.advance();
+      } else if c == '$' && !raw && self.peek() == '{' {
+        // Quotes in the embedded expression don't end the string.
+        // Without a closing brace the last character is read again below,
+        // so newlines are only counted up to it.
+        let end = closing_brace(&self.source, self.current + 1).min(self.source.len() - 1);
+        self.line += self.source[self.current..end].iter().filter(|c| **c == '\n').count();
+        self.current = end;
+        self.advance();
+      }
+      if self.previous() == '\n' {
         self.line += 1;
       }
+    }
+    let content: String = self.source[content_start..self.current].iter().collect();
+
+    // the closing quotes
+    let quotes = if multiline { 3 } else { 1 };
+    for _ in 0..quotes {
       self.advance();
     }
 
-    // unterminated string
-    if self.is_at_end() {
-      report(self.line, "Unterminated string.");
+    if raw {
+      let content = if multiline { strip_indentation(&content) } else { content };
+      self.add_token_with_literal(TokenType::STRING, Some(Literal::StringType(content)));
       return;
     }
 
-    // the closing "
-    self.advance();
+    // "a${b}c" is scanned as INTERPOLATION("a") b STRING("c"), the code
+    // is split off first so indentation is only stripped from the text.
+    let segments = split_interpolations(&content);
+    let segments = if multiline { strip_segments_indentation(segments) } else { segments };
+    let count = segments.len();
+    for (i, segment) in segments.into_iter().enumerate() {
+      match segment {
+        Segment::Text(text) => {
+          let token_type = if i == count - 1 { TokenType::STRING } else { TokenType::INTERPOLATION };
+          match unescape(&text) {
+            Ok(value) => self.add_token_with_literal(token_type, Some(Literal::StringType(value))),
+            Err(message) => self.error(&message),
+          }
+        }
+        Segment::Code(code, lines) => {
+          let mut scanner = Scanner::new(code.chars().collect());
+          scanner.line = first_line + lines;
+          scanner.scan_tokens();
+          self.tokens.extend(scanner.tokens.into_iter().filter(|t| t.token_type != TokenType::EOF));
+          self.diagnostics.extend(scanner.diagnostics);
+        }
+      }
+    }
+  }
 
-    // trim the surrounding quotes
-    let l = &self.source[self.start + 1..self.current - 1];
-    let literal_string: Literal = Literal::StringType(l.into_iter().collect());
-    self.add_token_with_literal(TokenType::STRING, Some(literal_string));
+  fn is_closing_triple_quote(&self) -> bool {
+    self.source.get(self.current..self.current + 3) == Some(&['"', '"', '"'][..])
   }
 
   fn peek_next(&self) -> char {
@@ -123,27 +189,30 @@ impl Scanner {
     c >= '0' && c <= '9'
   }
 
-  fn process_number_literal(&mut self) {
-    while self.is_digit(self.peek()) {
-      self.advance();
-    }
+  fn previous(&self) -> char {
+    self.source[self.current - 1]
+  }
 
-    if self.peek() == '.' && self.is_digit(self.peek_next()) {
-      // consume the "."
-      self.advance();
+  // The whole literal is consumed first, including malformed parts like
+  // the "abc" of 12abc, and validated afterwards.
+  fn process_number_literal(&mut self) {
+    let is_radix = self.previous() == '0' && matches!(self.peek(), 'x' | 'X' | 'b' | 'B' | 'o' | 'O');
 
-      while self.is_digit(self.peek()) {
+    loop {
+      let c = self.peek();
+      let is_exponent_sign = (c == '+' || c == '-') && !is_radix && matches!(self.previous(), 'e' | 'E') && self.is_digit(self.peek_next());
+      if self.is_alphanumeric(c) || (c == '.' && self.is_digit(self.peek_next())) || is_exponent_sign {
         self.advance();
+      } else {
+        break;
       }
     }
 
     let l = &self.source[self.start..self.current];
-    let number_as_string: String = l.into_iter().collect();
-    if let Ok(n) = number_as_string.parse::<f64>() {
-      let literal_number: Literal = Literal::NumberType(n);
-      self.add_token_with_literal(TokenType::NUMBER, Some(literal_number));
-    } else {
-      report(self.line, "Invalid number");
+    let text: String = l.iter().collect();
+    match parse_number_literal(&text) {
+      Ok(n) => self.add_token_with_literal(TokenType::NUMBER, Some(n)),
+      Err(message) => self.error(&message),
     }
   }
 
@@ -177,10 +246,55 @@ impl Scanner {
       '}' => self.add_token(TokenType::RIGHTBRACE),
       ',' => self.add_token(TokenType::COMMA),
       '.' => self.add_token(TokenType::DOT),
-      '-' => self.add_token(TokenType::MINUS),
-      '+' => self.add_token(TokenType::PLUS),
+      '-' => {
+        if self.is_next_match('-') {
+          self.add_token(TokenType::MINUSMINUS)
+        } else if self.is_next_match('=') {
+          self.add_token(TokenType::MINUSEQUAL)
+        } else {
+          self.add_token(TokenType::MINUS)
+        }
+      }
+      '+' => {
+        if self.is_next_match('+') {
+          self.add_token(TokenType::PLUSPLUS)
+        } else if self.is_next_match('=') {
+          self.add_token(TokenType::PLUSEQUAL)
+        } else {
+          self.add_token(TokenType::PLUS)
+        }
+      }
       ';' => self.add_token(TokenType::SEMICOLON),
-      '*' => self.add_token(TokenType::STAR),
+      '%' => {
+        if self.is_next_match('=') {
+          self.add_token(TokenType::PERCENTEQUAL)
+        } else {
+          self.add_token(TokenType::PERCENT)
+        }
+      }
+      '&' => self.add_token(TokenType::AMPERSAND),
+      '|' => self.add_token(TokenType::PIPE),
+      '^' => self.add_token(TokenType::CARET),
+      '~' => self.add_token(TokenType::TILDE),
+      ':' => self.add_token(TokenType::COLON),
+      '?' => {
+        if self.is_next_match('?') {
+          self.add_token(TokenType::QUESTIONQUESTION)
+        } else if self.is_next_match('.') {
+          self.add_token(TokenType::QUESTIONDOT)
+        } else {
+          self.add_token(TokenType::QUESTION)
+        }
+      }
+      '*' => {
+        if self.is_next_match('*') {
+          self.add_token(TokenType::STARSTAR)
+        } else if self.is_next_match('=') {
+          self.add_token(TokenType::STAREQUAL)
+        } else {
+          self.add_token(TokenType::STAR)
+        }
+      }
       '!' => {
         if self.is_next_match('=') {
           self.add_token(TokenType::BANGEQUAL)
@@ -198,6 +312,8 @@ impl Scanner {
       '<' => {
         if self.is_next_match('=') {
           self.add_token(TokenType::LESSEQUAL)
+        } else if self.is_next_match('<') {
+          self.add_token(TokenType::LESSLESS)
         } else {
           self.add_token(TokenType::LESS)
         }
@@ -205,6 +321,8 @@ impl Scanner {
       '>' => {
         if self.is_next_match('=') {
           self.add_token(TokenType::GREATEREQUAL)
+        } else if self.is_next_match('>') {
+          self.add_token(TokenType::GREATERGREATER)
         } else {
           self.add_token(TokenType::GREATER)
         }
@@ -222,8 +340,10 @@ impl Scanner {
             }
           }
           if self.is_at_end() {
-            report(self.line, "Unterminated comment.");
+            self.error("Unterminated comment.");
           }
+        } else if self.is_next_match('=') {
+          self.add_token(TokenType::SLASHEQUAL);
         } else {
           self.add_token(TokenType::SLASH);
         }
@@ -232,10 +352,14 @@ impl Scanner {
         // ignore whitespace
       }
       '\n' => self.line += 1,
-      '"' => self.process_string_literal(),
+      '"' => self.process_string_literal(false),
+      'r' if self.peek() == '"' => {
+        self.advance();
+        self.process_string_literal(true);
+      }
       '0'..='9' => self.process_number_literal(),
       'a'..='z' | 'A'..='Z' | '_' => self.process_identifier(),
-      _ => report(self.line, "Unexpected character."),
+      _ => self.error("Unexpected character."),
     }
   }
 
@@ -254,6 +378,17 @@ impl Scanner {
 
     self.tokens.clone()
   }
+
+  // Like scan_tokens, but fails with all diagnostics when the source has
+  // errors.
+  pub fn scan(&mut self) -> Result<Vec<Token>, RloxError> {
+    let tokens = self.scan_tokens();
+    if !self.diagnostics.is_empty() {
+      return Err(RloxError::ScannerError(self.diagnostics.join("\n")));
+    }
+
+    Ok(tokens)
+  }
 }
 
 #[cfg(test)]
@@ -300,8 +435,7 @@ mod tests {
       TokenType::STAR,
       TokenType::PLUS,
       TokenType::MINUS,
-      TokenType::SLASH,
-      TokenType::EQUAL,
+      TokenType::SLASHEQUAL,
       TokenType::LESS,
       TokenType::GREATER,
       TokenType::LESSEQUAL,
@@ -333,8 +467,7 @@ mod tests {
       TokenType::STAR,
       TokenType::PLUS,
       TokenType::MINUS,
-      TokenType::SLASH,
-      TokenType::EQUAL,
+      TokenType::SLASHEQUAL,
       TokenType::LESS,
       TokenType::GREATER,
       TokenType::STRING,
@@ -369,8 +502,7 @@ mod tests {
       TokenType::STAR,
       TokenType::PLUS,
       TokenType::MINUS,
-      TokenType::SLASH,
-      TokenType::EQUAL,
+      TokenType::SLASHEQUAL,
       TokenType::LESS,
       TokenType::NUMBER,
       TokenType::GREATER,
@@ -407,8 +539,7 @@ mod tests {
       TokenType::STAR,
       TokenType::PLUS,
       TokenType::MINUS,
-      TokenType::SLASH,
-      TokenType::EQUAL,
+      TokenType::SLASHEQUAL,
       TokenType::LESS,
       TokenType::NUMBER,
       TokenType::GREATER,
@@ -421,4 +552,121 @@ mod tests {
       assert_eq!(assert_tokens[i].name(), t.token_type.name());
     }
   }
+
+  #[test]
+  fn scan_integer_and_float_literals() {
+    let mut scanner = Scanner::new("42 42.0 9007199254740993".chars().collect());
+    let tokens = scanner.scan_tokens();
+
+    assert!(matches!(tokens[0].literal, Some(Literal::IntegerType(42))));
+    assert!(matches!(tokens[1].literal, Some(Literal::NumberType(n)) if n == 42.0));
+    assert!(matches!(tokens[2].literal, Some(Literal::IntegerType(9007199254740993))));
+  }
+
+  #[test]
+  fn scan_bigint_and_decimal_literals() {
+    let mut scanner = Scanner::new("123456789012345678901234567890n 19.99m 5m 7 n".chars().collect());
+    let tokens = scanner.scan_tokens();
+
+    assert_eq!(tokens[0].literal.as_ref().unwrap().inspect(), "123456789012345678901234567890n");
+    assert_eq!(tokens[1].literal.as_ref().unwrap().inspect(), "19.99m");
+    assert_eq!(tokens[2].literal.as_ref().unwrap().inspect(), "5m");
+    assert!(matches!(tokens[3].literal, Some(Literal::IntegerType(7))));
+    assert_eq!(tokens[4].token_type.name(), TokenType::IDENTIFIER.name());
+  }
+
+  #[test]
+  fn scan_extended_number_literals() {
+    let source = "0xFF 0b1010 0o17 1_000 1e3 2.5e-3 7n 1.5m".chars().collect();
+    let tokens = Scanner::new(source).scan().unwrap();
+
+    let literals: Vec<String> = tokens.iter().filter_map(|t| t.literal.as_ref().map(|l| l.inspect())).collect();
+    assert_eq!(literals, vec!["255", "10", "15", "1000", "1000", "0.0025", "7n", "1.5m"]);
+  }
+
+  #[test]
+  fn scan_reports_all_diagnostics() {
+    let source = "var a = 12abc;\nvar b = 0x;\n@".chars().collect();
+    let result = Scanner::new(source).scan();
+
+    assert_eq!(
+      result.err().map(|e| e.to_string()),
+      Some("[line 1] Error: Invalid number literal '12abc'.\n[line 2] Error: Invalid number literal '0x'.\n[line 3] Error: Unexpected character.".to_string())
+    );
+  }
+
+  #[test]
+  fn scan_assignment_operators() {
+    let source = "+= -= *= /= %= ++ -- + - ** /".chars().collect();
+    let tokens = Scanner::new(source).scan().unwrap();
+
+    let assert_tokens = vec![
+      TokenType::PLUSEQUAL,
+      TokenType::MINUSEQUAL,
+      TokenType::STAREQUAL,
+      TokenType::SLASHEQUAL,
+      TokenType::PERCENTEQUAL,
+      TokenType::PLUSPLUS,
+      TokenType::MINUSMINUS,
+      TokenType::PLUS,
+      TokenType::MINUS,
+      TokenType::STARSTAR,
+      TokenType::SLASH,
+      TokenType::EOF,
+    ];
+
+    let token_types: Vec<&str> = tokens.iter().map(|t| t.token_type.name()).collect();
+    assert_eq!(token_types, assert_tokens.iter().map(|t| t.name()).collect::<Vec<&str>>());
+  }
+
+  #[test]
+  fn scan_string_forms() {
+    let source = "\"a\\tb\" r\"a\\tb\" \"\"\"\n  x\n    y\n  \"\"\" \"\\\"\"\nend".chars().collect();
+    let tokens = Scanner::new(source).scan().unwrap();
+
+    let literals: Vec<String> = tokens.iter().filter_map(|t| t.literal.as_ref().map(|l| l.to_string())).collect();
+    assert_eq!(literals, vec!["a\tb", "a\\tb", "x\n  y", "\""]);
+    assert_eq!(tokens[4].line, 5);
+  }
+
+  #[test]
+  fn scan_reports_unterminated_interpolation_line() {
+    let source = "print \"x ${1 + 2\";\n".chars().collect();
+    let result = Scanner::new(source).scan();
+
+    assert_eq!(result.err().map(|e| e.to_string()), Some("[line 2] Error: Unterminated string.".to_string()));
+  }
+
+  #[test]
+  fn scan_reports_invalid_escapes() {
+    let source = "\"\\q\";\n\"\\u{110000}\"; \"unterminated".chars().collect();
+    let result = Scanner::new(source).scan();
+
+    assert_eq!(
+      result.err().map(|e| e.to_string()),
+      Some("[line 1] Error: Invalid escape sequence '\\q'.\n[line 2] Error: Invalid unicode escape '\\u{110000}'.\n[line 2] Error: Unterminated string.".to_string())
+    );
+  }
+
+  #[test]
+  fn scan_interpolated_strings() {
+    let source = "\"a${b}c${d + 1}\" r\"${raw}\"".chars().collect();
+    let tokens = Scanner::new(source).scan().unwrap();
+
+    let assert_tokens = vec![
+      TokenType::INTERPOLATION,
+      TokenType::IDENTIFIER,
+      TokenType::INTERPOLATION,
+      TokenType::IDENTIFIER,
+      TokenType::PLUS,
+      TokenType::NUMBER,
+      TokenType::STRING,
+      TokenType::STRING,
+      TokenType::EOF,
+    ];
+
+    let token_types: Vec<&str> = tokens.iter().map(|t| t.token_type.name()).collect();
+    assert_eq!(token_types, assert_tokens.iter().map(|t| t.name()).collect::<Vec<&str>>());
+    assert_eq!(tokens[7].literal.as_ref().map(|l| l.to_string()), Some("${raw}".to_string()));
+  }
 }
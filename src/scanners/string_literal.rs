@@ -0,0 +1,264 @@
+// Processing of the text between the quotes of a string literal:
+//
+//   "a\tb\n"  "\u{1F600}"    escape sequences
+//   r"C:\dir"                raw, backslashes are kept
+//   """                      multi-line, the line breaks after the opening
+//     text                   and before the closing quotes and the
+//   """                      indentation common to all lines are removed
+//   "Hello ${name}!"         interpolation, except in raw strings
+//
+// Errors are messages for the scanner diagnostics.
+
+pub enum Segment {
+  Text(String),
+  // The source of an embedded expression and the number of lines before
+  // it in the string.
+  Code(String, usize),
+}
+
+// Splits the text of a string at the ${...} it embeds.
+pub fn split_interpolations(text: &str) -> Vec<Segment> {
+  let chars: Vec<char> = text.chars().collect();
+  let mut segments = Vec::new();
+  let mut current = String::new();
+  let mut lines = 0;
+
+  let mut i = 0;
+  while i < chars.len() {
+    match chars[i] {
+      '\\' => {
+        current.extend(chars.get(i..i + 2).unwrap_or(&chars[i..]));
+        i += 2;
+      }
+      '$' if chars.get(i + 1) == Some(&'{') => {
+        let end = closing_brace(&chars, i + 2);
+        let code: String = chars[i + 2..end.min(chars.len())].iter().collect();
+        lines += current.matches('\n').count();
+        segments.push(Segment::Text(std::mem::take(&mut current)));
+        let code_lines = code.matches('\n').count();
+        segments.push(Segment::Code(code, lines));
+        lines += code_lines;
+        i = end + 1;
+      }
+      c => {
+        current.push(c);
+        i += 1;
+      }
+    }
+  }
+
+  segments.push(Segment::Text(current));
+  segments
+}
+
+// Index of the } closing the ${ before start, or the length of chars when
+// there is none. Braces in strings nested in the expression don't count.
+pub fn closing_brace(chars: &[char], start: usize) -> usize {
+  let mut depth = 1;
+  let mut i = start;
+  while i < chars.len() {
+    match chars[i] {
+      '{' => depth += 1,
+      '}' => {
+        depth -= 1;
+        if depth == 0 {
+          return i;
+        }
+      }
+      '"' => {
+        i += 1;
+        while i < chars.len() && chars[i] != '"' {
+          if chars[i] == '\\' {
+            i += 1;
+          }
+          i += 1;
+        }
+      }
+      _ => (),
+    }
+    i += 1;
+  }
+
+  chars.len()
+}
+
+pub fn unescape(text: &str) -> Result<String, String> {
+  let mut result = String::with_capacity(text.len());
+  let mut chars = text.chars();
+
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      result.push(c);
+      continue;
+    }
+
+    match chars.next() {
+      Some('n') => result.push('\n'),
+      Some('t') => result.push('\t'),
+      Some('r') => result.push('\r'),
+      Some('0') => result.push('\0'),
+      Some('\\') => result.push('\\'),
+      Some('"') => result.push('"'),
+      Some('\'') => result.push('\''),
+      Some('$') => result.push('$'),
+      Some('u') => result.push(unicode_escape(&mut chars)?),
+      Some(c) => return Err(format!("Invalid escape sequence '\\{}'.", c)),
+      None => return Err("Invalid escape sequence at end of string.".to_string()),
+    }
+  }
+
+  Ok(result)
+}
+
+// \u{1F600}, one to six hex digits.
+fn unicode_escape(chars: &mut std::str::Chars) -> Result<char, String> {
+  let rest = chars.as_str();
+  let invalid = |escape: &str| format!("Invalid unicode escape '\\u{}'.", escape);
+
+  if !rest.starts_with('{') {
+    return Err(invalid(""));
+  }
+
+  let end = match rest.find('}') {
+    Some(end) => end,
+    None => return Err(invalid(rest)),
+  };
+
+  let escape = &rest[..=end];
+  let digits = &rest[1..end];
+  if digits.is_empty() || digits.len() > 6 {
+    return Err(invalid(escape));
+  }
+
+  let c = u32::from_str_radix(digits, 16).ok().and_then(char::from_u32).ok_or_else(|| invalid(escape))?;
+  *chars = rest[end + 1..].chars();
+  Ok(c)
+}
+
+pub fn strip_indentation(text: &str) -> String {
+  match strip_segments_indentation(vec![Segment::Text(text.to_string())]).pop() {
+    Some(Segment::Text(text)) => text,
+    _ => String::new(),
+  }
+}
+
+// Only the text is stripped, the code of ${...} is kept as written and
+// its line breaks don't start new lines of the string.
+pub fn strip_segments_indentation(segments: Vec<Segment>) -> Vec<Segment> {
+  let mut lines: Vec<Vec<Segment>> = vec![vec![]];
+  for segment in segments {
+    match segment {
+      Segment::Text(text) => {
+        let parts: Vec<&str> = text.split('\n').collect();
+        for (i, part) in parts.iter().enumerate() {
+          if i > 0 {
+            lines.push(vec![]);
+          }
+          let part = if i + 1 < parts.len() { part.strip_suffix('\r').unwrap_or(part) } else { part };
+          lines.last_mut().unwrap().push(Segment::Text(part.to_string()));
+        }
+      }
+      code => lines.last_mut().unwrap().push(code),
+    }
+  }
+
+  let is_blank = |line: &Vec<Segment>| line.iter().all(|s| matches!(s, Segment::Text(t) if t.trim().is_empty()));
+  if lines.len() > 1 && matches!(lines[0].as_slice(), [Segment::Text(t)] if t.is_empty()) {
+    lines.remove(0);
+  }
+  if lines.len() > 1 && lines.last().is_some_and(is_blank) {
+    lines.pop();
+  }
+
+  let indentation = lines.iter()
+    .filter(|line| !is_blank(line))
+    .map(|line| match line.first() {
+      Some(Segment::Text(t)) => t.len() - t.trim_start().len(),
+      _ => 0,
+    })
+    .min()
+    .unwrap_or(0);
+
+  let mut stripped: Vec<Segment> = Vec::new();
+  for (i, line) in lines.into_iter().enumerate() {
+    for (j, segment) in line.into_iter().enumerate() {
+      let segment = match segment {
+        Segment::Text(t) if j == 0 => Segment::Text(format!("{}{}", if i > 0 { "\n" } else { "" }, t.get(indentation..).unwrap_or(""))),
+        segment => segment,
+      };
+      match (stripped.last_mut(), segment) {
+        (Some(Segment::Text(last)), Segment::Text(t)) => last.push_str(&t),
+        (_, segment) => stripped.push(segment),
+      }
+    }
+  }
+  stripped
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_escapes() {
+    let test_input = [
+      ("a\\tb\\n", "a\tb\n"),
+      ("say \\\"hi\\\"", "say \"hi\""),
+      ("back\\\\slash", "back\\slash"),
+      ("\\u{1F600}", "\u{1F600}"),
+      ("\\u{e9}t\\u{E9}", "été"),
+      ("no escapes", "no escapes"),
+      ("\\${not code}", "${not code}"),
+    ];
+
+    for (input, expected) in test_input.iter() {
+      assert_eq!(unescape(input), Ok(expected.to_string()), "{}", input);
+    }
+  }
+
+  #[test]
+  fn test_invalid_escapes() {
+    let test_input = [
+      ("\\q", "Invalid escape sequence '\\q'."),
+      ("\\u1234", "Invalid unicode escape '\\u'."),
+      ("\\u{}", "Invalid unicode escape '\\u{}'."),
+      ("\\u{D800}", "Invalid unicode escape '\\u{D800}'."),
+      ("\\u{1234567}", "Invalid unicode escape '\\u{1234567}'."),
+      ("\\u{12", "Invalid unicode escape '\\u{12'."),
+    ];
+
+    for (input, expected) in test_input.iter() {
+      assert_eq!(unescape(input), Err(expected.to_string()), "{}", input);
+    }
+  }
+
+  fn describe(segments: Vec<Segment>) -> Vec<String> {
+    segments.into_iter().map(|segment| match segment {
+      Segment::Text(text) => format!("text {}", text),
+      Segment::Code(code, lines) => format!("code {} at {}", code, lines),
+    }).collect()
+  }
+
+  #[test]
+  fn test_split_interpolations() {
+    assert_eq!(describe(split_interpolations("Hello ${name}!")), vec!["text Hello ", "code name at 0", "text !"]);
+    assert_eq!(describe(split_interpolations("${a}${ {b} }")), vec!["text ", "code a at 0", "text ", "code  {b}  at 0", "text "]);
+    assert_eq!(describe(split_interpolations("${f(\"}\")}")), vec!["text ", "code f(\"}\") at 0", "text "]);
+    assert_eq!(describe(split_interpolations("a\n${b}\\${c}")), vec!["text a\n", "code b at 1", "text \\${c}"]);
+    assert_eq!(describe(split_interpolations("no code")), vec!["text no code"]);
+  }
+
+  #[test]
+  fn test_strip_indentation() {
+    assert_eq!(strip_indentation("\n    Hello\n      World\n    "), "Hello\n  World");
+    assert_eq!(strip_indentation("\n  a\n\n  b\n"), "a\n\nb");
+    assert_eq!(strip_indentation("one line"), "one line");
+    assert_eq!(strip_indentation("\r\n  a\r\n  b\r\n  "), "a\nb");
+  }
+
+  #[test]
+  fn test_strip_indentation_keeps_code() {
+    let segments = split_interpolations("\n    a ${f(\n  1,\n        2)} b\n      ${x}\n    ");
+    assert_eq!(describe(strip_segments_indentation(segments)), vec!["text a ", "code f(\n  1,\n        2) at 1", "text  b\n  ", "code x at 4", "text "]);
+  }
+}
@@ -0,0 +1,200 @@
+use crate::rlox::*;
+use crate::scanners::{self, Scanner};
+use std::path::{Path, PathBuf};
+
+type Stm = std::rc::Rc<std::cell::RefCell<dyn Stmt<RloxType>>>;
+
+pub struct TestOutcome {
+  pub name: String,
+  pub result: Result<(), String>,
+}
+
+#[derive(Default)]
+pub struct TestSummary {
+  pub passed: usize,
+  pub failed: usize,
+  pub filtered_out: usize,
+}
+
+// Runs all tests found in given files and directories, returns true
+// when none of them failed.
+pub fn run_tests(paths: &[&str], filter: Option<&str>, policy: &FsPolicy) -> Result<bool, RloxError> {
+  let mut files = Vec::new();
+  for path in paths {
+    let path = Path::new(path);
+    if !path.is_dir() && !is_source_file(path) {
+      let reason = if path.exists() { "is not a .rl file or a directory" } else { "does not exist" };
+      return Err(not_found(format!("{} {}", path.display(), reason)));
+    }
+    discover_files(path, &mut files)?;
+  }
+  if files.is_empty() {
+    return Err(not_found(format!("no .rl files found in {}", paths.join(", "))));
+  }
+  files.sort();
+
+  let mut summary = TestSummary::default();
+
+  for file in files {
+    println!("{}", file.display());
+    let data = scanners::read_source_code(&file.to_string_lossy())?;
+    let (outcomes, filtered_out) = run_test_source(data, filter, policy);
+    summary.filtered_out += filtered_out;
+
+    for outcome in outcomes {
+      match outcome.result {
+        Ok(()) => {
+          summary.passed += 1;
+          println!("  ok     {}", outcome.name);
+        }
+        Err(message) => {
+          summary.failed += 1;
+          println!("  FAILED {}", outcome.name);
+          for line in message.lines() {
+            println!("    {}", line);
+          }
+        }
+      }
+    }
+  }
+
+  println!(
+    "\ntest result: {}. {} passed; {} failed; {} filtered out",
+    if summary.failed == 0 { "ok" } else { "FAILED" },
+    summary.passed,
+    summary.failed,
+    summary.filtered_out,
+  );
+
+  Ok(summary.failed == 0)
+}
+
+fn discover_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), RloxError> {
+  if path.is_dir() {
+    for entry in std::fs::read_dir(path)? {
+      discover_files(&entry?.path(), files)?;
+    }
+  } else if is_source_file(path) {
+    files.push(path.to_path_buf());
+  }
+
+  Ok(())
+}
+
+fn is_source_file(path: &Path) -> bool {
+  path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("rl")
+}
+
+fn not_found(message: String) -> RloxError {
+  RloxError::IOError(std::io::Error::new(std::io::ErrorKind::NotFound, message))
+}
+
+// Every test runs in its own interpreter, after the top level
+// declarations of the file it's declared in.
+pub fn run_test_source(data: Vec<char>, filter: Option<&str>, policy: &FsPolicy) -> (Vec<TestOutcome>, usize) {
+  let mut scanner = Scanner::new(data);
+  let statements: Vec<Stm> = match scanner.scan().and_then(|tokens| Parser::new(tokens).parse()) {
+    Ok(s) => s,
+    Err(e) => {
+      let outcome = TestOutcome {
+        name: "<parse>".to_string(),
+        result: Err(e.to_string()),
+      };
+      return (vec![outcome], 0);
+    }
+  };
+
+  let mut outcomes = Vec::new();
+  let mut filtered_out = 0;
+
+  for statement in statements.iter() {
+    let statement = statement.borrow();
+    let test = match statement.as_any().downcast_ref::<Test<RloxType>>() {
+      Some(t) => t,
+      None => continue,
+    };
+
+    let name = test.name.literal.clone().map_or(String::new(), |n| n.to_string());
+    if let Some(f) = filter {
+      if !name.contains(f) {
+        filtered_out += 1;
+        continue;
+      }
+    }
+
+    outcomes.push(TestOutcome {
+      result: run_single_test(&statements, test, policy).map_err(|e| e.to_string()),
+      name,
+    });
+  }
+
+  (outcomes, filtered_out)
+}
+
+fn run_single_test(statements: &[Stm], test: &Test<RloxType>, policy: &FsPolicy) -> Result<(), RloxError> {
+  let interpreter = Interpreter::new();
+  interpreter.set_fs_policy(policy.clone());
+  let resolver = Resolver::new(interpreter.clone());
+  resolver.resolve_statements(statements.to_vec())?;
+
+  for statement in statements {
+    interpreter.execute(statement.clone())?;
+  }
+  interpreter.run_test(test)?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn run(input: &str, filter: Option<&str>) -> (Vec<TestOutcome>, usize) {
+    run_test_source(input.chars().collect(), filter, &FsPolicy::working_directory())
+  }
+
+  #[test]
+  fn test_passing_and_failing_tests() {
+    let (outcomes, _) = run("fun add(a, b) { return a + b; } test \"adds\" { assert_eq(add(1, 2), 3); } test \"fails\" { assert_eq(add(1, 2), 4); }", None);
+
+    assert_eq!(outcomes.len(), 2);
+    assert_eq!(outcomes[0].name, "adds");
+    assert!(outcomes[0].result.is_ok());
+    assert_eq!(outcomes[1].name, "fails");
+    assert_eq!(outcomes[1].result.as_ref().unwrap_err(), "assert_eq failed (- expected, + actual):\n- 4\n+ 3");
+  }
+
+  #[test]
+  fn test_filter_by_name() {
+    let (outcomes, filtered_out) = run("test \"first\" { assert(true); } test \"second\" { assert(false); }", Some("first"));
+
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].name, "first");
+    assert_eq!(filtered_out, 1);
+  }
+
+  #[test]
+  fn test_tests_are_isolated() {
+    let (outcomes, _) = run("var count = 0; test \"one\" { count = count + 1; assert_eq(count, 1); } test \"two\" { count = count + 1; assert_eq(count, 1); }", None);
+
+    assert!(outcomes.iter().all(|o| o.result.is_ok()));
+  }
+
+  #[test]
+  fn test_assert_throws() {
+    let (outcomes, _) = run("fun bad() { return 1 + \"a\"; } fun good() { return 1; } test \"throws\" { var msg = assert_throws(bad); assert(msg != nil); } test \"no throw\" { assert_throws(good); }", None);
+
+    assert!(outcomes[0].result.is_ok());
+    assert_eq!(outcomes[1].result.as_ref().unwrap_err(), "assert_throws failed: <fn good > did not throw.");
+  }
+
+  #[test]
+  fn test_missing_paths() {
+    let missing = run_tests(&["/nonexistent"], None, &FsPolicy::disabled()).unwrap_err();
+    assert_eq!(missing.to_string(), "/nonexistent does not exist");
+    let not_source = run_tests(&["Cargo.toml"], None, &FsPolicy::disabled()).unwrap_err();
+    assert_eq!(not_source.to_string(), "Cargo.toml is not a .rl file or a directory");
+    let empty = run_tests(&["src/bin"], None, &FsPolicy::disabled()).unwrap_err();
+    assert_eq!(empty.to_string(), "no .rl files found in src/bin");
+  }
+}
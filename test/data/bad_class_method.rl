@@ -0,0 +1,5 @@
+class Math {
+  class twice(n) {
+    return this.double(n); // Error at 'this': Can't use 'this' in a class method or field.
+  }
+}
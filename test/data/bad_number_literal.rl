@@ -0,0 +1,2 @@
+print 0b102; // [line 1] Error: Invalid number literal '0b102'.
+print 1.5n; // [line 2] Error: Bigint literal '1.5n' can't have a fraction or exponent.
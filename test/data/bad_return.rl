@@ -1 +1 @@
-return "at top  level";
\ No newline at end of file
+return "at top  level"; // Error at 'return': Can't return from top-level code.
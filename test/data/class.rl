@@ -6,16 +6,16 @@ class DevonshireCream {
   }
 }
 
-print DevonshireCream; // Prints "DevonshireCream".
+print DevonshireCream; // expect: DevonshireCream
 
 // Two
 
 class Bagel {}
 var bagel = Bagel();
-print bagel;
+print bagel; // expect: Bagel instance
 
 bagel.description = "Good";
-print bagel.description;
+print bagel.description; // expect: Good
 
 // Three
 
@@ -25,7 +25,7 @@ class Bacon {
     }
 }
 
-Bacon().eat();
+Bacon().eat(); // expect: Crunch crunch crunch!
 
 // Four
 
@@ -38,7 +38,7 @@ class Cake {
 
 var cake = Cake();
 cake.flavor = "German chocolate";
-cake.taste(); // Prints "The German chocolate cake is delicious!".
+cake.taste(); // expect: The German chocolate cake is delicious!
 
 // Five
 
@@ -48,8 +48,9 @@ class Foo {
   }
 }
 
-var foo = Foo();
-print foo.init();
+var foo = Foo(); // expect: Foo instance
+print foo.init(); // expect: Foo instance
+// expect: Foo instance
 
 // Six
 
@@ -71,7 +72,7 @@ class BostonCream < Doughnut {
 }
 
 var first = BostonCream().cook();
-print first;
+print first; // expect: Fry until golden brown.
 
 var second = BostonCream().cookAnother();
-print second;
+print second; // expect: Fry until golden. Then pipe full of custard and coat with chocolate.
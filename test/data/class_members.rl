@@ -0,0 +1,76 @@
+class Math {
+  class PI = 3.14159;
+  class calls = 0;
+
+  class square(n) {
+    Math.calls += 1;
+    return n * n;
+  }
+
+  class circleArea(r) {
+    return Math.PI * Math.square(r);
+  }
+}
+
+print Math.square(4); // expect: 16
+print Math.circleArea(1); // expect: 3.14159
+print Math.calls; // expect: 2
+Math.calls = 10;
+print Math.calls; // expect: 10
+
+class Point {
+  class ORIGIN = Point(0, 0);
+  class count;
+
+  init(x, y) {
+    this.x = x;
+    this.y = y;
+  }
+
+  class of(x, y) {
+    return Point(x, y);
+  }
+
+  norm() {
+    return this.x * this.x + this.y * this.y;
+  }
+}
+
+print Point.ORIGIN.x; // expect: 0
+print Point.of(3, 4).norm(); // expect: 25
+print Point.count; // expect: null
+print Point.ORIGIN == Point.ORIGIN; // expect: true
+
+class Point3 < Point {
+  init(x, y, z) {
+    super.init(x, y);
+    this.z = z;
+  }
+}
+
+print Point3.ORIGIN.y; // expect: 0
+print Point3.of(1, 2).norm(); // expect: 5
+
+fun counter() {
+  class Counter {
+    class total = 0;
+    class add(n) {
+      Counter.total += n;
+      return Counter.total;
+    }
+  }
+  Counter.add(2);
+  return Counter.add(3);
+}
+print counter(); // expect: 5
+
+class Ref {}
+var a = Ref();
+a.me = a;
+var b = Ref();
+b.me = b;
+print a == b; // expect: false
+print a == a.me; // expect: true
+print Ref() == Ref(); // expect: false
+
+print Math.cube(2); // expect runtime error: Undefined property 'cube' for class Math.
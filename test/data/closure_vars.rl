@@ -4,9 +4,9 @@ var a = "global";
     print a;
   }
 
-  showA();
+  showA(); // expect: global
   var a = "block";
-  showA();
+  showA(); // expect: global
 }
 
 fun test() {
@@ -15,4 +15,6 @@ fun test() {
   return a;
 }
 
-print test();
\ No newline at end of file
+print test();
+// expect: global
+// expect: global!
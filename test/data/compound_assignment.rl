@@ -0,0 +1,27 @@
+var x = 1;
+x += 2;
+print x; // expect: 3
+x *= 4;
+print x; // expect: 12
+print x++; // expect: 12
+print ++x; // expect: 14
+print x--; // expect: 14
+print x; // expect: 13
+
+class Counter {
+  init() {
+    this.count = 0;
+  }
+}
+
+var counter = Counter();
+var calls = 0;
+fun next() {
+  calls += 1;
+  return counter;
+}
+
+next().count += 10;
+next().count++;
+print counter.count; // expect: 11
+print calls; // expect: 2
@@ -0,0 +1,27 @@
+class Address {
+  init(city) {
+    this.city = city;
+  }
+}
+
+class User {
+  init(address) {
+    this.address = address;
+  }
+
+  describe() {
+    return this.address?.city ?? "unknown";
+  }
+}
+
+var user = User(Address("Lisbon"));
+var nobody = nil;
+
+print user?.address.city; // expect: Lisbon
+print nobody?.address.city; // expect: null
+print nobody?.describe(); // expect: null
+print User(nil).describe(); // expect: unknown
+print nobody ?? "guest"; // expect: guest
+print 0 ?? 1; // expect: 0
+print nobody == nil ? "none" : "some"; // expect: none
+print 1 < 2 ? 1 < 0 ? "a" : "b" : "c"; // expect: b
@@ -0,0 +1,9 @@
+var price = 19.99m;
+print price * 3; // expect: 59.97
+print 0.1m + 0.2m == 0.3m; // expect: true
+print 0.1 + 0.2 == 0.3; // expect: false
+print 9223372036854775807n * 10; // expect: 92233720368547758070
+print number(1.5m) + 0.5; // expect: 2
+print decimal(0.1) + 0.2m; // expect: 0.3
+print bigint("100000000000000000000") / 3; // expect: 33333333333333333333
+print price + 0.01; // expect runtime error: unsupported operand type(s) for PLUS: can't mix decimal and number, convert with number() or decimal()
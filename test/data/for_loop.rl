@@ -5,4 +5,26 @@ for (var b = 1; a < 10000; b = temp + b) {
   print a;
   temp = a;
   a = b;
-}
\ No newline at end of file
+}
+
+// expect: 0
+// expect: 1
+// expect: 1
+// expect: 2
+// expect: 3
+// expect: 5
+// expect: 8
+// expect: 13
+// expect: 21
+// expect: 34
+// expect: 55
+// expect: 89
+// expect: 144
+// expect: 233
+// expect: 377
+// expect: 610
+// expect: 987
+// expect: 1597
+// expect: 2584
+// expect: 4181
+// expect: 6765
@@ -0,0 +1,19 @@
+print format("{} + {} = {}", 1, 2, 1 + 2); // expect: 1 + 2 = 3
+print format("{:.2}", 2 / 3.0); // expect: 0.67
+print format("[{:>8.2}]", 3.14159); // expect: [    3.14]
+print format("[{:<6}|{:^6}|{:>6}]", "ab", "cd", "ef"); // expect: [ab    |  cd  |    ef]
+print format("{0} {1} {0}", "a", "b"); // expect: a b a
+print format("{:#x} {:b} {:08.3}", 255, 5, -1.5); // expect: 0xff 101 -001.500
+print format("{:.3e}", 123456); // expect: 1.235e5
+print format("{{literal}}"); // expect: {literal}
+
+class Row {
+  init(name, total) {
+    this.name = name;
+    this.total = total;
+  }
+}
+print format("{name:<8}{total:>10.2}", Row("apples", 12.5)); // expect: apples       12.50
+print format("{name} has {count}", json.parse("{\"name\": \"rlox\", \"count\": 3}")); // expect: rlox has 3
+
+format(); // expect runtime error: Expected at least 1 arguments but got 0.
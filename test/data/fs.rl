@@ -0,0 +1,26 @@
+print fs.read("test/data/fs/input.txt"); // expect: first line
+// expect: second line
+// expect: 
+var lines = fs.lines("test/data/fs/input.txt");
+print lines.len(); // expect: 2
+print lines.get(1); // expect: second line
+print fs.exists("test/data/fs/input.txt"); // expect: true
+print fs.exists("test/data/fs/missing.txt"); // expect: false
+print fs.list("test/data/fs"); // expect: ["input.txt"]
+
+var dir = "target/rlox-fs-test";
+fs.mkdir(dir + "/nested");
+fs.write(dir + "/out.txt", "a");
+fs.append(dir + "/out.txt", "b");
+print fs.read(dir + "/out.txt"); // expect: ab
+print fs.list(dir); // expect: ["nested", "out.txt"]
+fs.remove(dir + "/out.txt");
+fs.remove(dir + "/nested");
+fs.remove(dir);
+print fs.exists(dir); // expect: false
+
+fun outsideRoot() {
+  fs.read("/etc/hostname");
+}
+assert_throws(outsideRoot);
+fs.read("test/data/fs/missing.txt"); // expect runtime error: fs.read('test/data/fs/missing.txt') failed: not found.
@@ -0,0 +1,5 @@
+print 9007199254740993; // expect: 9007199254740993
+print 7 / 2; // expect: 3
+print 7 / 2.0; // expect: 3.5
+print 1 == 1.0; // expect: true
+print 9223372036854775807 + 1; // expect runtime error: Integer overflow.
@@ -0,0 +1,29 @@
+var name = "world";
+print "Hello ${name}!"; // expect: Hello world!
+print "1 + 2 = ${1 + 2}"; // expect: 1 + 2 = 3
+
+class Point {
+  init(x, y) {
+    this.x = x;
+    this.y = y;
+  }
+
+  toString() {
+    return "(${this.x}, ${this.y})";
+  }
+}
+
+print "point ${Point(1, 2)}"; // expect: point (1, 2)
+print "not \${interpolated}"; // expect: not ${interpolated}
+print r"raw ${name}"; // expect: raw ${name}
+
+var block = """
+    list: ${"a,
+  b".split(",").join(
+      "|")}
+      end
+    """;
+print block;
+// expect: list: a|
+// expect:   b
+// expect:   end
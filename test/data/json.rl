@@ -0,0 +1,30 @@
+var config = json.parse("{\"name\": \"rlox\", \"version\": 2, \"ratio\": 0.5, \"tags\": [\"a\", \"b\"], \"owner\": {\"id\": null}}");
+print config.name; // expect: rlox
+print config.version + 1; // expect: 3
+print config.ratio; // expect: 0.5
+print config.tags.get(1); // expect: b
+print config.owner.id; // expect: null
+print config; // expect: Object instance
+
+config.version = 3;
+print json.stringify(config, nil); // expect: {"name":"rlox","owner":{"id":null},"ratio":0.5,"tags":["a","b"],"version":3}
+
+class Point {
+  init(x, y) {
+    this.x = x;
+    this.y = y;
+  }
+}
+print json.stringify(Point(1, 2), 2);
+// expect: {
+// expect:   "x": 1,
+// expect:   "y": 2
+// expect: }
+print json.stringify("tab\there", nil); // expect: "tab\there"
+
+fun badValue() {
+  json.stringify(clock, nil);
+}
+print assert_throws(badValue); // expect: Can't convert function to JSON.
+
+json.parse("{\"a\": }"); // expect runtime error: Invalid JSON at line 1, column 7: expected a value.
@@ -0,0 +1,4 @@
+fun bad() {
+  var a = "first";
+  var a = "second"; // Error at 'a': Already variable with this name in this scope.
+}
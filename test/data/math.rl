@@ -0,0 +1,21 @@
+print math.sqrt(16); // expect: 4
+print math.pow(2, 10); // expect: 1024
+print math.abs(-5); // expect: 5
+print math.abs(-2.5); // expect: 2.5
+print math.floor(2.7); // expect: 2
+print math.ceil(2.1); // expect: 3
+print math.round(2.5); // expect: 3
+print math.min(3, 7); // expect: 3
+print math.max(3, 7.5); // expect: 7.5
+print math.floor(math.PI * 100); // expect: 314
+print math.log10(1000); // expect: 3
+print math.INF > 1000000; // expect: true
+print math.NAN == math.NAN; // expect: false
+
+var a = math.Random(42);
+var b = math.Random(42);
+print a.next() == b.next(); // expect: true
+print a.int(1, 6) == b.int(1, 6); // expect: true
+var n = a.int(1, 6);
+print n >= 1 and n <= 6; // expect: true
+print math.sqrt("x"); // expect runtime error: math.sqrt() expects a number, not string.
@@ -0,0 +1,9 @@
+print 1_000_000; // expect: 1000000
+print 0xFF; // expect: 255
+print 0b1010; // expect: 10
+print 0o755; // expect: 493
+print 1e3; // expect: 1000
+print 2.5e-3; // expect: 0.0025
+print 0xFFn; // expect: 255
+print 1_000.50m; // expect: 1000.50
+print 0xFF + 1; // expect: 256
@@ -0,0 +1,16 @@
+print 7 % 3; // expect: 1
+print -7 % 3; // expect: -1
+print 7 div 2; // expect: 3
+print -7 div 2; // expect: -4
+print 2 ** 10; // expect: 1024
+print -2 ** 2; // expect: -4
+print 2 ** 3 ** 2; // expect: 512
+print 2n ** 100; // expect: 1267650600228229401496703205376
+print 6 & 3; // expect: 2
+print 6 | 3; // expect: 7
+print 6 ^ 3; // expect: 5
+print ~5; // expect: -6
+print 1 << 4; // expect: 16
+print -16 >> 2; // expect: -4
+print (1 + 2) * 3; // expect: 9
+print 1.5 & 1; // expect runtime error: unsupported operand type(s) for AMPERSAND: both operand types must be integer
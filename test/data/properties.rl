@@ -0,0 +1,72 @@
+class Rect {
+  init(w, h) {
+    this.w = w;
+    this.h = h;
+  }
+
+  area {
+    return this.w * this.h;
+  }
+
+  width {
+    return this.w;
+  }
+
+  set width(value) {
+    if (value < 0) value = 0;
+    this.w = value;
+  }
+
+  set(key, value) {
+    return key + "=" + value;
+  }
+}
+
+var r = Rect(2, 3);
+print r.area; // expect: 6
+r.width = 5;
+print r.area; // expect: 15
+r.width = -1;
+print r.width; // expect: 0
+r.width += 4;
+print r.width; // expect: 4
+print r.width++; // expect: 4
+print r.width; // expect: 5
+print r.set("a", "1"); // expect: a=1
+
+class Square < Rect {
+  init(side) {
+    super.init(side, side);
+  }
+
+  set side(value) {
+    this.w = value;
+    this.h = value;
+  }
+}
+
+var s = Square(2);
+s.side = 3;
+print s.area; // expect: 9
+print "${s.area} and ${r.area}"; // expect: 9 and 15
+
+class Padded < Rect {
+  area {
+    return super.area + 1;
+  }
+}
+
+print Padded(2, 3).area; // expect: 7
+
+class Plain < Rect {
+  area() {
+    return "method";
+  }
+}
+
+print Plain(2, 3).area(); // expect: method
+var p = Plain(2, 3);
+p.area = 1;
+print p.area; // expect: 1
+
+r.area = 1; // expect runtime error: Property 'area' of Rect has no setter.
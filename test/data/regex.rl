@@ -0,0 +1,21 @@
+var date = Regex.compile("(\\d{4})-(\\d{2})-(\\d{2})");
+print date; // expect: /(\d{4})-(\d{2})-(\d{2})/
+print date.test("released 2024-03-01"); // expect: true
+print date.test("no date"); // expect: false
+print date.match("released 2024-03-01"); // expect: ["2024-03-01", "2024", "03", "01"]
+print date.match("no date"); // expect: null
+print Regex.compile("a(x)?b").match("ab"); // expect: ["ab", nil]
+print Regex.compile("\\d+").findAll("1 22 333"); // expect: ["1", "22", "333"]
+print date.replace("2024-03-01 and 2025-12-31", "$3/$2/$1"); // expect: 01/03/2024 and 31/12/2025
+print Regex.compile("(?P<word>\\w+)").replace("hi there", "<\${word}>"); // expect: <hi> <there>
+print Regex.compile("\\s*,\\s*").split("a , b,c"); // expect: ["a", "b", "c"]
+
+var level = Regex.compile("^(ERROR|WARN)");
+var lines = "ERROR disk full\nINFO ok\nWARN slow".split("\n");
+var count = 0;
+for (var i = 0; i < lines.len(); i = i + 1) {
+  if (level.test(lines.get(i))) count = count + 1;
+}
+print count; // expect: 2
+
+Regex.compile("(unclosed"); // expect runtime error: Invalid regex '(unclosed' at line 21: unclosed group.
@@ -0,0 +1,3 @@
+print "before"; // expect: before
+print 1 + "one"; // expect runtime error: unsupported operand type(s) for PLUS: both operand types must be number
+print "after";
@@ -2,4 +2,4 @@ fun sayHi(first, last) {
   print "Hi, " + first + " " + last + "!";
 }
 
-sayHi("Dear", "Reader");
\ No newline at end of file
+sayHi("Dear", "Reader"); // expect: Hi, Dear Reader!
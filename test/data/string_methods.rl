@@ -0,0 +1,28 @@
+var text = "  Hello, Wörld  ".trim();
+print text; // expect: Hello, Wörld
+print text.len(); // expect: 12
+print text.upper(); // expect: HELLO, WÖRLD
+print text.lower(); // expect: hello, wörld
+print text.find("Wö"); // expect: 7
+print text.substring(7, 12); // expect: Wörld
+print text.replace("Hello", "Bye"); // expect: Bye, Wörld
+print text.startsWith("Hell"); // expect: true
+print text.endsWith("x"); // expect: false
+print "ab".repeat(3); // expect: ababab
+print "😀!".codePoints(); // expect: [128512, 33]
+
+var words = "a,b,c".split(",");
+print words; // expect: ["a", "b", "c"]
+print words.len(); // expect: 3
+print words.get(1); // expect: b
+words.push("d");
+print words.join("-"); // expect: a-b-c-d
+
+var cycle = "a".split(",");
+cycle.push(cycle);
+var other = "a".split(",");
+other.push(other);
+print cycle == other; // expect: true
+print cycle == words; // expect: false
+print "héllo".chars().join(" "); // expect: h é l l o
+print "abc".substring(2, 9); // expect runtime error: Substring range 2..9 out of bounds for string of length 3.
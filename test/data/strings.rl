@@ -0,0 +1,15 @@
+print "tab\tseparated"; // expect: tab	separated
+print "say \"hi\""; // expect: say "hi"
+print "smile \u{1F600}"; // expect: smile 😀
+print r"C:\dir\new"; // expect: C:\dir\new
+
+var letter = """
+    Dear reader,
+      thanks for reading.
+    """;
+print letter;
+// expect: Dear reader,
+// expect:   thanks for reading.
+print "after"; // expect: after
+print "see // not a comment"; // expect: see // not a comment
+print "a \"// b\" c"; // expect: a "// b" c
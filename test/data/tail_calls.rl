@@ -0,0 +1,13 @@
+// Calls in tail position don't grow the stack.
+fun isEven(n) {
+  if (n == 0) return true;
+  return isOdd(n - 1);
+}
+
+fun isOdd(n) {
+  if (n == 0) return false;
+  return isEven(n - 1);
+}
+
+print isEven(100000); // expect: true
+print isOdd(7); // expect: true
@@ -0,0 +1,28 @@
+var start = time.nanos();
+print time.nanos() >= start; // expect: true
+print time.millis() >= 0; // expect: true
+
+var d = time.datetime(2024, 2, 29, 13, 45, 30);
+print d; // expect: 2024-02-29T13:45:30Z
+print d.year(); // expect: 2024
+print d.month(); // expect: 2
+print d.weekday(); // expect: Thursday
+print d.format("%d/%m/%Y %H:%M"); // expect: 29/02/2024 13:45
+print d.timestamp(); // expect: 1709214330
+
+var tokyo = d.inZone("JST");
+print tokyo; // expect: 2024-02-29T22:45:30+09:00
+print tokyo.offset(); // expect: +09:00
+print tokyo == d.inZone("+09:00"); // expect: true
+
+var later = d.add(time.duration(90));
+print later; // expect: 2024-02-29T13:47:00Z
+print later.since(d).seconds(); // expect: 90
+print later.since(d).millis(); // expect: 90000
+
+var parsed = time.parse("2024-03-01 08:00 -0500", "%Y-%m-%d %H:%M %z");
+print parsed; // expect: 2024-03-01T08:00:00-05:00
+print parsed.inZone("UTC").hour(); // expect: 13
+print time.date(2024, 1, 1); // expect: 2024-01-01T00:00:00Z
+print time.fromTimestamp(0); // expect: 1970-01-01T00:00:00Z
+print time.date(2023, 2, 29); // expect runtime error: time.date() got an invalid date.
@@ -0,0 +1,47 @@
+// Command line parsing of the rlox binary, scripts and the test
+// subcommand share the first positional argument.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn rlox(dir: &PathBuf, args: &[&str]) -> Output {
+  Command::new(env!("CARGO_BIN_EXE_rlox"))
+    .current_dir(dir)
+    .args(args)
+    .output()
+    .unwrap()
+}
+
+fn scratch(name: &str) -> PathBuf {
+  let dir = std::env::temp_dir().join(format!("rlox-cli-{}-{}", name, std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  dir
+}
+
+#[test]
+fn test_script_named_like_subcommand() {
+  let dir = scratch("script");
+  fs::write(dir.join("te"), "print \"te\";").unwrap();
+  fs::create_dir_all(dir.join("test")).unwrap();
+  fs::write(dir.join("test/x.rl"), "print \"x\";").unwrap();
+
+  for (script, expected) in [("te", "te\n"), ("test/x.rl", "x\n")].iter() {
+    let output = rlox(&dir, &[script]);
+    assert_eq!(output.status.code(), Some(0), "{}: {}", script, String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), *expected);
+  }
+  fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_subcommand() {
+  let dir = scratch("subcommand");
+  fs::write(dir.join("tests.rl"), "test \"passes\" { assert(true); }").unwrap();
+
+  let output = rlox(&dir, &["test", "tests.rl"]);
+  assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stdout));
+  assert!(String::from_utf8_lossy(&output.stdout).contains("1 passed"));
+  fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,162 @@
+// Golden file tests, every script under test/ is run with the rlox
+// binary and its output is compared against annotations embedded in
+// the script:
+//
+//   // expect: <line>                  expected line on stdout
+//   // expect runtime error: <message> expected error, exits with 70
+//   // [line N] Error at 'x': <message> expected compile error, exits with 65
+//   // Error at 'x': <message>
+//
+// These are the annotations used by the Crafting Interpreters test suite,
+// so its test directories can be copied under test/ as they are.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const EXIT_COMPILE_ERROR: i32 = 65;
+const EXIT_RUNTIME_ERROR: i32 = 70;
+
+// Suite directories covering chapters rlox doesn't have a runner for.
+const SKIPPED_DIRS: &[&str] = &["benchmark", "expressions", "limit", "scanning"];
+
+#[derive(Default)]
+struct Expectations {
+  output: Vec<String>,
+  runtime_error: Option<String>,
+  compile_errors: Vec<String>,
+}
+
+impl Expectations {
+  fn parse(source: &str) -> Expectations {
+    let mut expectations = Expectations::default();
+
+    for line in source.lines() {
+      let comment = match comment_start(line) {
+        Some(i) => &line[i + 3..],
+        None => continue,
+      };
+
+      if let Some(output) = comment.strip_prefix("expect: ") {
+        expectations.output.push(output.to_string());
+      } else if let Some(message) = comment.strip_prefix("expect runtime error: ") {
+        expectations.runtime_error = Some(message.to_string());
+      } else if comment.starts_with("[c line") {
+        // Only reported by clox.
+        continue;
+      } else if comment.starts_with("Error") || comment.starts_with("[line") || comment.starts_with("[java line") {
+        // Error locations aren't reported by rlox, only the message is compared.
+        if let Some(i) = comment.find(": ") {
+          expectations.compile_errors.push(comment[i + 2..].to_string());
+        }
+      }
+    }
+
+    expectations
+  }
+
+  fn exit_code(&self) -> i32 {
+    if !self.compile_errors.is_empty() {
+      EXIT_COMPILE_ERROR
+    } else if self.runtime_error.is_some() {
+      EXIT_RUNTIME_ERROR
+    } else {
+      0
+    }
+  }
+}
+
+// Start of the "// " comment on the line, ignoring "// " in strings.
+fn comment_start(line: &str) -> Option<usize> {
+  let bytes = line.as_bytes();
+  let mut in_string = false;
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'\\' if in_string => i += 1,
+      b'"' => in_string = !in_string,
+      b'/' if !in_string && line[i..].starts_with("// ") => return Some(i),
+      _ => (),
+    }
+    i += 1;
+  }
+  None
+}
+
+fn discover_scripts(dir: &Path, scripts: &mut Vec<PathBuf>) {
+  for entry in fs::read_dir(dir).unwrap() {
+    let path = entry.unwrap().path();
+    if path.is_dir() {
+      let name = path.file_name().unwrap().to_string_lossy().to_string();
+      if !SKIPPED_DIRS.contains(&name.as_str()) {
+        discover_scripts(&path, scripts);
+      }
+    } else if let Some(ext) = path.extension() {
+      if ext == "rl" || ext == "lox" {
+        scripts.push(path);
+      }
+    }
+  }
+}
+
+fn check_script(script: &Path) -> Vec<String> {
+  let source = fs::read_to_string(script).unwrap();
+  let expectations = Expectations::parse(&source);
+
+  // test/data/fs.rl writes under target/.
+  let output = Command::new(env!("CARGO_BIN_EXE_rlox"))
+    .arg("--fs")
+    .arg("write")
+    .arg("-i")
+    .arg(script)
+    .output()
+    .unwrap();
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+
+  let mut failures = Vec::new();
+
+  let actual: Vec<&str> = stdout.lines().collect();
+  if actual != expectations.output {
+    failures.push(format!("expected output {:?}, got {:?}", expectations.output, actual));
+  }
+
+  if let Some(message) = &expectations.runtime_error {
+    if stderr.lines().next() != Some(message.as_str()) {
+      failures.push(format!("expected runtime error {:?}, got {:?}", message, stderr));
+    }
+  } else if !expectations.compile_errors.is_empty() {
+    for message in &expectations.compile_errors {
+      if !stderr.contains(message.as_str()) {
+        failures.push(format!("expected compile error {:?}, got {:?}", message, stderr));
+      }
+    }
+  } else if !stderr.is_empty() {
+    failures.push(format!("unexpected error output {:?}", stderr));
+  }
+
+  let exit_code = output.status.code().unwrap_or(-1);
+  if exit_code != expectations.exit_code() {
+    failures.push(format!("expected exit code {}, got {}", expectations.exit_code(), exit_code));
+  }
+
+  failures
+}
+
+#[test]
+fn test_scripts_match_expectations() {
+  let mut scripts = Vec::new();
+  discover_scripts(&Path::new(env!("CARGO_MANIFEST_DIR")).join("test"), &mut scripts);
+  scripts.sort();
+
+  assert!(!scripts.is_empty());
+
+  let mut report = String::new();
+  for script in &scripts {
+    for failure in check_script(script) {
+      report.push_str(&format!("{}: {}\n", script.display(), failure));
+    }
+  }
+
+  assert!(report.is_empty(), "\n{}", report);
+}